@@ -0,0 +1,498 @@
+use crate::cube_move::token_at;
+use crate::Analysis;
+use crate::Axis;
+use crate::Move;
+use crate::MoveCount;
+use crate::ParseError;
+use crate::RotationDirection;
+use crate::Simplification;
+use std::str::FromStr;
+
+// The most moves a repeated group may give when parsed.
+const MAX_REPEATED_MOVES: usize = 100_000;
+
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct Algorithm {
+    pub moves: Vec<Move>,
+}
+
+impl std::fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for (i, cube_move) in self.moves.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{}", cube_move)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Algorithm {
+    type Err = ParseError;
+
+    // Besides moves, reads commutators [A, B], conjugates [A: B] and groups
+    // (A), nested in any way. Like a move, a bracket or group may be
+    // followed by a count repeating it and a prime inverting it: (A)3,
+    // [A, B]' or [A, B]2.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let chars: Vec<char> = s.chars().collect();
+        let (algorithm, end) = parse_sequence(&chars, 0)?;
+        if end < chars.len() {
+            return Err(ParseError::new(end, token_at(&chars, end)));
+        }
+        Ok(algorithm)
+    }
+}
+
+// Reads moves and groups up to the end or a character closing a group,
+// and returns them with the position of that character.
+fn parse_sequence(chars: &[char], start: usize) -> Result<(Algorithm, usize), ParseError> {
+    let mut algorithm = Algorithm::default();
+    let mut position = start;
+    loop {
+        match chars.get(position) {
+            None | Some(',') | Some(':') | Some(']') | Some(')') => {
+                return Ok((algorithm, position));
+            }
+            Some(c) if c.is_whitespace() => position += 1,
+            Some('[') => {
+                let (first, middle) = parse_sequence(chars, position + 1)?;
+                let separator = chars.get(middle).cloned();
+                let (second, end) = parse_sequence(chars, middle + 1)?;
+                let group = match separator {
+                    Some(',') => first.commutator(&second),
+                    Some(':') => first.conjugate(&second),
+                    _ => return Err(unexpected(chars, middle)),
+                };
+                if chars.get(end) != Some(&']') {
+                    return Err(unexpected(chars, end));
+                }
+                let (group, end) = parse_suffix(chars, group, end + 1)?;
+                algorithm.moves.extend(group.moves);
+                position = end;
+            }
+            Some('(') => {
+                let (group, end) = parse_sequence(chars, position + 1)?;
+                if chars.get(end) != Some(&')') {
+                    return Err(unexpected(chars, end));
+                }
+                let (group, end) = parse_suffix(chars, group, end + 1)?;
+                algorithm.moves.extend(group.moves);
+                position = end;
+            }
+            Some(_) => {
+                let (cube_move, end) = Move::parse(chars, position)?;
+                algorithm.moves.push(cube_move);
+                position = end;
+            }
+        }
+    }
+}
+
+// Reads the count and prime after a group, and returns the group they make
+// with the position after them. Counts giving more than `MAX_REPEATED_MOVES`
+// moves are refused.
+fn parse_suffix(
+    chars: &[char],
+    group: Algorithm,
+    start: usize,
+) -> Result<(Algorithm, usize), ParseError> {
+    let digits: String = chars[start..]
+        .iter()
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+    let mut group = group;
+    if !digits.is_empty() {
+        let error = ParseError::new(start, digits.clone());
+        let times: usize = digits.parse().map_err(|_| error.clone())?;
+        match group.len().checked_mul(times) {
+            Some(length) if length <= MAX_REPEATED_MOVES => group = group.repeat(times),
+            _ => return Err(error),
+        }
+    }
+    let mut end = start + digits.len();
+    if chars.get(end) == Some(&'\'') {
+        group = group.inverse();
+        end += 1;
+    }
+    Ok((group, end))
+}
+
+fn unexpected(chars: &[char], position: usize) -> ParseError {
+    let token = if position < chars.len() {
+        token_at(chars, position)
+    } else {
+        String::new()
+    };
+    ParseError::new(position, token)
+}
+
+impl Algorithm {
+    pub fn new(moves: Vec<Move>) -> Algorithm {
+        Algorithm { moves }
+    }
+
+    pub fn len(&self) -> usize {
+        self.moves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+
+    // Undoes the algorithm: the moves are reversed and each is inverted.
+    pub fn inverse(&self) -> Algorithm {
+        Algorithm::new(self.moves.iter().rev().map(|m| m.inverse()).collect())
+    }
+
+    // The algorithm seen in a mirror across the axis: `Axis::X` swaps left
+    // and right, `Axis::Z` front and back.
+    pub fn mirror(&self, axis: Axis) -> Algorithm {
+        Algorithm::new(self.moves.iter().map(|m| m.mirror(axis)).collect())
+    }
+
+    pub fn then(&self, other: &Algorithm) -> Algorithm {
+        let mut moves = self.moves.clone();
+        moves.extend(other.moves.iter().cloned());
+        Algorithm::new(moves)
+    }
+
+    pub fn repeat(&self, times: usize) -> Algorithm {
+        Algorithm::new(self.moves.repeat(times))
+    }
+
+    // [A: B], that is A B A'.
+    pub fn conjugate(&self, other: &Algorithm) -> Algorithm {
+        self.then(other).then(&self.inverse())
+    }
+
+    // [A, B], that is A B A' B'.
+    pub fn commutator(&self, other: &Algorithm) -> Algorithm {
+        self.then(other)
+            .then(&self.inverse())
+            .then(&other.inverse())
+    }
+
+    // The cycles and order of the algorithm, applied to a solved cube.
+    pub fn analyze(&self) -> Analysis {
+        Analysis::new(self)
+    }
+
+    pub fn move_count(&self) -> MoveCount {
+        self.moves
+            .iter()
+            .fold(MoveCount::default(), |count, cube_move| {
+                let (layers, turns) = match cube_move {
+                    Move::Rotation(_, _) => (0, 0),
+                    Move::Slice(_, direction) => (2, qtm_turns(*direction)),
+                    Move::Face(_, direction) | Move::Wide(_, direction) => {
+                        (1, qtm_turns(*direction))
+                    }
+                };
+                MoveCount {
+                    htm: count.htm + layers,
+                    qtm: count.qtm + layers * turns,
+                    stm: count.stm + layers.min(1),
+                }
+            })
+    }
+
+    // Merges turns of the same layer and drops those adding up to nothing.
+    // A turn is also merged past a turn of the opposite face, so R L R'
+    // becomes L.
+    pub fn simplify(&self) -> Simplification {
+        let mut moves: Vec<Move> = Vec::new();
+        for cube_move in self.moves.iter() {
+            let mut merged = false;
+            for i in (0..moves.len()).rev() {
+                if same_layer(moves[i], *cube_move) {
+                    let turns = moves[i].direction().quarter_turns()
+                        + cube_move.direction().quarter_turns();
+                    match RotationDirection::from_quarter_turns(turns) {
+                        Some(direction) => moves[i] = moves[i].with_direction(direction),
+                        None => {
+                            moves.remove(i);
+                        }
+                    }
+                    merged = true;
+                    break;
+                }
+                if !opposite_faces(moves[i], *cube_move) {
+                    break;
+                }
+            }
+            if !merged {
+                moves.push(*cube_move);
+            }
+        }
+        let algorithm = Algorithm::new(moves);
+        Simplification {
+            before: self.move_count(),
+            after: algorithm.move_count(),
+            algorithm,
+        }
+    }
+}
+
+// Quarter turns counted either way, as QTM does: an anticlockwise turn is
+// one quarter turn, not three clockwise ones.
+fn qtm_turns(direction: RotationDirection) -> usize {
+    let turns = direction.quarter_turns();
+    turns.min(4 - turns)
+}
+
+fn same_layer(first: Move, second: Move) -> bool {
+    first.with_direction(RotationDirection::Clockwise)
+        == second.with_direction(RotationDirection::Clockwise)
+}
+
+fn opposite_faces(first: Move, second: Move) -> bool {
+    match (first, second) {
+        (Move::Face(first, _), Move::Face(second, _)) => first.opposite() == second,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Axis;
+    use crate::ColorFacet;
+    use crate::RotationDirection;
+    use crate::RubiksCube;
+    use crate::Slice;
+
+    #[test]
+    fn test_parse_should_map_face_letters_to_colors() {
+        let algorithm: Algorithm = "U D L R F B".parse().unwrap();
+        let faces = vec![
+            ColorFacet::White,
+            ColorFacet::Yellow,
+            ColorFacet::Green,
+            ColorFacet::Blue,
+            ColorFacet::Red,
+            ColorFacet::Orange,
+        ];
+        let moves: Vec<Move> = faces
+            .into_iter()
+            .map(|face| Move::Face(face, RotationDirection::Clockwise))
+            .collect();
+        assert_eq!(algorithm.moves, moves);
+    }
+
+    #[test]
+    fn test_parse_should_read_suffixes() {
+        let algorithm: Algorithm = "R U' F2".parse().unwrap();
+        assert_eq!(
+            algorithm.moves,
+            vec![
+                Move::Face(ColorFacet::Blue, RotationDirection::Clockwise),
+                Move::Face(ColorFacet::White, RotationDirection::Anticlockwise),
+                Move::Face(ColorFacet::Red, RotationDirection::HalfTurn),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_should_read_slices() {
+        let algorithm: Algorithm = "M' E2 S".parse().unwrap();
+        assert_eq!(
+            algorithm.moves,
+            vec![
+                Move::Slice(Slice::Middle, RotationDirection::Anticlockwise),
+                Move::Slice(Slice::Equator, RotationDirection::HalfTurn),
+                Move::Slice(Slice::Standing, RotationDirection::Clockwise),
+            ]
+        );
+        assert_eq!(algorithm.to_string(), "M' E2 S");
+    }
+
+    #[test]
+    fn test_parse_should_read_cube_rotations() {
+        let algorithm: Algorithm = "x y' z2".parse().unwrap();
+        assert_eq!(
+            algorithm.moves,
+            vec![
+                Move::Rotation(Axis::X, RotationDirection::Clockwise),
+                Move::Rotation(Axis::Y, RotationDirection::Anticlockwise),
+                Move::Rotation(Axis::Z, RotationDirection::HalfTurn),
+            ]
+        );
+        assert_eq!(algorithm.to_string(), "x y' z2");
+    }
+
+    #[test]
+    fn test_parse_should_read_wide_moves() {
+        let algorithm: Algorithm = "Rw r' Uw2 f2".parse().unwrap();
+        assert_eq!(
+            algorithm.moves,
+            vec![
+                Move::Wide(ColorFacet::Blue, RotationDirection::Clockwise),
+                Move::Wide(ColorFacet::Blue, RotationDirection::Anticlockwise),
+                Move::Wide(ColorFacet::White, RotationDirection::HalfTurn),
+                Move::Wide(ColorFacet::Red, RotationDirection::HalfTurn),
+            ]
+        );
+        assert_eq!(algorithm.to_string(), "Rw Rw' Uw2 Fw2");
+        let error = "R Mw".parse::<Algorithm>().unwrap_err();
+        assert_eq!(error, ParseError::new(2, "Mw".to_string()));
+    }
+
+    #[test]
+    fn test_parse_should_accept_missing_and_extra_spaces() {
+        let compact: Algorithm = "RUR'U'".parse().unwrap();
+        let spaced: Algorithm = "  R U  R'   U' ".parse().unwrap();
+        assert_eq!(compact, spaced);
+        assert_eq!(compact.len(), 4);
+    }
+
+    #[test]
+    fn test_parse_should_report_position_and_token() {
+        let error = "R U X2 F".parse::<Algorithm>().unwrap_err();
+        assert_eq!(error, ParseError::new(4, "X2".to_string()));
+        let error = "R U3 F".parse::<Algorithm>().unwrap_err();
+        assert_eq!(error, ParseError::new(2, "U3".to_string()));
+    }
+
+    #[test]
+    fn test_parse_should_read_groups() {
+        let parse = |text: &str| text.parse::<Algorithm>().unwrap().to_string();
+        assert_eq!(parse("[R, U]"), "R U R' U'");
+        assert_eq!(parse("[F: R U R' U']"), "F R U R' U' F'");
+        assert_eq!(parse("[F: [R, U]]"), "F R U R' U' F'");
+        assert_eq!(
+            parse("(R U R' U')3"),
+            parse("R U R' U' R U R' U' R U R' U'")
+        );
+        assert_eq!(
+            parse("x (R2 U)2 [R':[U2,L]]y"),
+            "x R2 U R2 U R' U2 L U2 L' R y"
+        );
+        assert_eq!(parse("(R U)"), "R U");
+        assert_eq!(parse("(M U)0 F"), "F");
+        assert_eq!(parse("[R, U]'"), "U R U' R'");
+        assert_eq!(parse("[R, U]2"), "R U R' U' R U R' U'");
+        assert_eq!(parse("(R U)2' F"), "U' R' U' R' F");
+    }
+
+    #[test]
+    fn test_parse_should_refuse_huge_counts() {
+        let error = "(R U)9999999999999".parse::<Algorithm>().unwrap_err();
+        assert_eq!(error, ParseError::new(5, "9999999999999".to_string()));
+        let error = "(R)99999999999999999999999"
+            .parse::<Algorithm>()
+            .unwrap_err();
+        assert_eq!(
+            error,
+            ParseError::new(3, "99999999999999999999999".to_string())
+        );
+        assert_eq!("(R)100000".parse::<Algorithm>().unwrap().len(), 100_000);
+    }
+
+    #[test]
+    fn test_parse_should_report_unclosed_groups() {
+        let error = "[R U]".parse::<Algorithm>().unwrap_err();
+        assert_eq!(error, ParseError::new(4, "]".to_string()));
+        let error = "[R, U".parse::<Algorithm>().unwrap_err();
+        assert_eq!(error, ParseError::new(5, "".to_string()));
+        let error = "(R U')) F".parse::<Algorithm>().unwrap_err();
+        assert_eq!(error, ParseError::new(6, ")".to_string()));
+        let error = "R U, F".parse::<Algorithm>().unwrap_err();
+        assert_eq!(error, ParseError::new(3, ",".to_string()));
+    }
+
+    #[test]
+    fn test_inverse_should_undo_the_algorithm() {
+        let algorithm: Algorithm = "R U2 M' x r F'".parse().unwrap();
+        assert_eq!(algorithm.inverse().to_string(), "F Rw' x' M U2 R'");
+        let cube = RubiksCube::new().apply(&algorithm);
+        assert!(cube.apply(&algorithm.inverse()).is_solved());
+    }
+
+    #[test]
+    fn test_mirror_should_swap_the_sides() {
+        let sune: Algorithm = "R U R' U R U2 R'".parse().unwrap();
+        assert_eq!(sune.mirror(Axis::X).to_string(), "L' U' L U' L' U2 L");
+        assert_eq!(sune.mirror(Axis::Z).to_string(), "R' U' R U' R' U2 R");
+        let algorithm: Algorithm = "F M E S x y z".parse().unwrap();
+        assert_eq!(algorithm.mirror(Axis::X).to_string(), "F' M E' S' x y' z'");
+        assert_eq!(algorithm.mirror(Axis::Z).to_string(), "B' M' E' S x' y' z");
+    }
+
+    #[test]
+    fn test_conjugate_and_commutator_should_compose() {
+        let setup: Algorithm = "F".parse().unwrap();
+        let sexy = "R"
+            .parse::<Algorithm>()
+            .unwrap()
+            .commutator(&"U".parse().unwrap());
+        assert_eq!(sexy.to_string(), "R U R' U'");
+        assert_eq!(setup.conjugate(&sexy).to_string(), "F R U R' U' F'");
+        assert_eq!(sexy.repeat(6).simplify().before.htm, 24);
+        assert!(RubiksCube::new().apply(&sexy.repeat(6)).is_solved());
+    }
+
+    #[test]
+    fn test_display_should_round_trip() {
+        let text = "R U R' U' F2 B D' L2";
+        let algorithm: Algorithm = text.parse().unwrap();
+        assert_eq!(algorithm.to_string(), text);
+        assert_eq!(
+            algorithm.to_string().parse::<Algorithm>().unwrap(),
+            algorithm
+        );
+    }
+
+    #[test]
+    fn test_simplify_should_cancel_and_merge_turns() {
+        let simplify = |text: &str| {
+            let algorithm: Algorithm = text.parse().unwrap();
+            algorithm.simplify().algorithm.to_string()
+        };
+        assert_eq!(simplify("R R'"), "");
+        assert_eq!(simplify("U U U"), "U'");
+        assert_eq!(simplify("R U U' R' F"), "F");
+        assert_eq!(simplify("R L R'"), "L");
+        assert_eq!(simplify("R L2 R L2"), "R2");
+        assert_eq!(simplify("M M' x x r2 r2"), "x2");
+        assert_eq!(simplify("R U R' U'"), "R U R' U'");
+        assert_eq!(
+            simplify("F R U R' U' F' F R U R' U' F'"),
+            "F R U R' U' R U R' U' F'"
+        );
+    }
+
+    #[test]
+    fn test_move_count_should_use_each_metric() {
+        let algorithm: Algorithm = "R U2 M' x r2 E2".parse().unwrap();
+        assert_eq!(
+            algorithm.move_count(),
+            MoveCount {
+                htm: 7,
+                qtm: 11,
+                stm: 5,
+            }
+        );
+        let simplification = "R U U' R' F2".parse::<Algorithm>().unwrap().simplify();
+        assert_eq!(simplification.before.qtm, 6);
+        assert_eq!(
+            simplification.after,
+            MoveCount {
+                htm: 1,
+                qtm: 2,
+                stm: 1
+            }
+        );
+        assert_eq!(
+            simplification.to_string(),
+            "F2 (5 HTM, 6 QTM, 5 STM before, 1 HTM, 2 QTM, 1 STM after)"
+        );
+    }
+
+    #[test]
+    fn test_display_should_be_canonical() {
+        let algorithm: Algorithm = "R2'  U".parse().unwrap();
+        assert_eq!(algorithm.to_string(), "R2 U");
+    }
+}
@@ -0,0 +1,193 @@
+use crate::cubie_cube::{CORNER_FACELETS, EDGE_FACELETS, FACES};
+use crate::Algorithm;
+use crate::PieceCycle;
+use crate::RubiksCube;
+
+// What an algorithm does to a solved cube, found by following where every
+// facet goes by its `Facet::index`.
+#[derive(PartialEq, Debug, Clone)]
+pub struct Analysis {
+    // For every facet position, face * 9 + position with faces up, right,
+    // front, down, left, back, the home position of the facet moved there.
+    pub facet_permutation: Vec<usize>,
+    pub corner_cycles: Vec<PieceCycle>,
+    pub edge_cycles: Vec<PieceCycle>,
+    pub center_cycles: Vec<PieceCycle>,
+    pub order: usize,
+}
+
+impl std::fmt::Display for Analysis {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let cycles = self
+            .corner_cycles
+            .iter()
+            .chain(self.edge_cycles.iter())
+            .chain(self.center_cycles.iter())
+            .map(|cycle| cycle.to_string())
+            .collect::<Vec<String>>();
+        write!(f, "{}, order {}", cycles.join(" "), self.order)
+    }
+}
+
+impl Analysis {
+    pub fn new(algorithm: &Algorithm) -> Analysis {
+        let cube = RubiksCube::new().apply(algorithm);
+        let facet_permutation: Vec<usize> = FACES
+            .iter()
+            .flat_map(|face| cube.face(*face).facets().to_vec())
+            .map(|facet| {
+                let face = FACES.iter().position(|f| *f == facet.color).unwrap();
+                face * 9 + facet.index as usize - 1
+            })
+            .collect();
+        let corners: Vec<Vec<usize>> = CORNER_FACELETS
+            .iter()
+            .map(|facelets| facelets.iter().map(|(f, p)| f * 9 + p).collect())
+            .collect();
+        let edges: Vec<Vec<usize>> = EDGE_FACELETS
+            .iter()
+            .map(|facelets| facelets.iter().map(|(f, p)| f * 9 + p).collect())
+            .collect();
+        let centers: Vec<Vec<usize>> = (0..6).map(|face| vec![face * 9 + 4]).collect();
+        let corner_cycles = piece_cycles(&facet_permutation, &corners);
+        let edge_cycles = piece_cycles(&facet_permutation, &edges);
+        let center_cycles = piece_cycles(&facet_permutation, &centers);
+        let order = corner_cycles
+            .iter()
+            .chain(edge_cycles.iter())
+            .chain(center_cycles.iter())
+            .fold(1, |order, cycle| lcm(order, cycle.order));
+        Analysis {
+            facet_permutation,
+            corner_cycles,
+            edge_cycles,
+            center_cycles,
+            order,
+        }
+    }
+}
+
+// Cycles of the pieces with the given facet positions. Pieces left in place unturned are not
+// part of any cycle.
+fn piece_cycles(facet_permutation: &[usize], pieces: &[Vec<usize>]) -> Vec<PieceCycle> {
+    let size = pieces[0].len();
+    // For every position, the piece moved there and which facet of the
+    // position its first facet is on, as `CubieCube` orients pieces.
+    let moved: Vec<(usize, usize)> = pieces
+        .iter()
+        .map(|facets| {
+            facets
+                .iter()
+                .enumerate()
+                .find_map(|(orientation, facet)| {
+                    let home = facet_permutation[*facet];
+                    let piece = pieces.iter().position(|p| p[0] == home)?;
+                    Some((piece, orientation))
+                })
+                .unwrap()
+        })
+        .collect();
+    let mut destinations = vec![0; pieces.len()];
+    for (position, (piece, _)) in moved.iter().enumerate() {
+        destinations[*piece] = position;
+    }
+    let mut seen = vec![false; pieces.len()];
+    let mut cycles = Vec::new();
+    for start in 0..pieces.len() {
+        let mut positions = Vec::new();
+        let mut orientation = 0;
+        let mut position = start;
+        while !seen[position] {
+            seen[position] = true;
+            positions.push(position);
+            position = destinations[position];
+            orientation += moved[position].1;
+        }
+        if positions.is_empty() || (positions.len() == 1 && orientation % size == 0) {
+            continue;
+        }
+        let orientation = orientation % size;
+        let turns = if orientation == 0 {
+            1
+        } else {
+            size / gcd(orientation, size)
+        };
+        cycles.push(PieceCycle {
+            positions: positions
+                .iter()
+                .map(|p| pieces[*p].iter().map(|f| FACES[f / 9].notation()).collect())
+                .collect(),
+            orientation: orientation as u8,
+            order: positions.len() * turns,
+        });
+    }
+    cycles
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+fn lcm(a: usize, b: usize) -> usize {
+    a / gcd(a, b) * b
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn analyze(text: &str) -> Analysis {
+        Analysis::new(&text.parse().unwrap())
+    }
+
+    #[test]
+    fn test_order_should_repeat_back_to_solved() {
+        let orders = [
+            ("", 1),
+            ("R", 4),
+            ("R2", 2),
+            ("R U R' U'", 6),
+            ("R U", 105),
+            ("R U R' U R U2 R'", 6),
+            ("M2 E2 S2", 2),
+            ("x", 4),
+            ("R U2 D' B D'", 1260),
+        ];
+        for (text, order) in orders.iter() {
+            let analysis = analyze(text);
+            assert_eq!(analysis.order, *order, "{}", text);
+            let algorithm: Algorithm = text.parse().unwrap();
+            let cube = RubiksCube::new().apply(&algorithm.repeat(*order));
+            assert_eq!(cube, RubiksCube::new());
+        }
+    }
+
+    #[test]
+    fn test_cycles_should_follow_the_pieces() {
+        let analysis = analyze("R U R' U'");
+        assert_eq!(
+            analysis.to_string(),
+            "(URF DFR)+ (ULB UBR)++ (UR UB FR), order 6"
+        );
+        assert_eq!(analysis.edge_cycles[0].order, 3);
+        let analysis = analyze("R U R' U R U2 R'");
+        assert_eq!(analysis.edge_cycles.len(), 1);
+        assert_eq!(analysis.corner_cycles.len(), 2);
+        let analysis = analyze("M2");
+        assert_eq!(analysis.center_cycles.len(), 2);
+        assert_eq!(analysis.center_cycles[0].positions, vec!["U", "D"]);
+    }
+
+    #[test]
+    fn test_facet_permutation_should_track_facet_indexes() {
+        let analysis = analyze("U");
+        assert_eq!(analysis.facet_permutation.len(), 54);
+        assert_eq!(analysis.facet_permutation[0], 6);
+        assert_eq!(analysis.facet_permutation[9], 45);
+        assert_eq!(analysis.facet_permutation[27], 27);
+    }
+}
@@ -0,0 +1,42 @@
+use crate::ColorFacet;
+use std::slice::Iter;
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+impl Axis {
+    pub fn iterator() -> Iter<'static, Axis> {
+        static AXIS: [Axis; 3] = [Axis::X, Axis::Y, Axis::Z];
+        AXIS.iter()
+    }
+
+    pub fn from_notation(letter: char) -> Option<Axis> {
+        match letter {
+            'x' => Some(Axis::X),
+            'y' => Some(Axis::Y),
+            'z' => Some(Axis::Z),
+            _ => None,
+        }
+    }
+
+    // The face a rotation around the axis turns the same way as.
+    pub fn face(self) -> ColorFacet {
+        match self {
+            Axis::X => ColorFacet::Blue,
+            Axis::Y => ColorFacet::White,
+            Axis::Z => ColorFacet::Red,
+        }
+    }
+
+    pub fn notation(self) -> char {
+        match self {
+            Axis::X => 'x',
+            Axis::Y => 'y',
+            Axis::Z => 'z',
+        }
+    }
+}
@@ -0,0 +1,454 @@
+use crate::pattern_databases::{PatternDatabase, PieceKind};
+use crate::solving_cube::{
+    corner_position, corner_solved, edge_position, edge_solved, from_side, solve_cross,
+    SolvingCube, SLOT_SIDES, UP_TURNS,
+};
+use crate::Algorithm;
+use crate::CubieCube;
+use crate::Move;
+use crate::RubiksCube;
+use crate::Solution;
+use crate::SolveError;
+use std::sync::OnceLock;
+
+// Turns of the up, right and front faces, numbered as in `Move::face_turn`.
+// Every first two layers case is solved with them from the slot's side.
+const F2L_MOVES: [usize; 9] = [0, 1, 2, 3, 4, 5, 6, 7, 8];
+
+// Pulls the pair out of the front right slot into the up layer.
+const EXTRACT: &str = "R U R'";
+
+const CORNER_PLACES: [&str; 4] = ["front right", "front left", "back left", "back right"];
+const EDGE_PLACES: [&str; 4] = ["right", "front", "left", "back"];
+const TWISTS: [&str; 3] = ["oriented", "twisted clockwise", "twisted anticlockwise"];
+const FLIPS: [&str; 2] = ["oriented", "flipped"];
+
+pub(crate) const OLL_CASES: [(&str, &str); 57] = [
+    ("OLL 1", "R U2 R2 F R F' U2 R' F R F'"),
+    ("OLL 2", "F R U R' U' F' f R U R' U' f'"),
+    ("OLL 3", "f R U R' U' f' U' F R U R' U' F'"),
+    ("OLL 4", "f R U R' U' f' U F R U R' U' F'"),
+    ("OLL 5", "r' U2 R U R' U r"),
+    ("OLL 6", "r U2 R' U' R U' r'"),
+    ("OLL 7", "r U R' U R U2 r'"),
+    ("OLL 8", "l' U' L U' L' U2 l"),
+    ("OLL 9", "R U R' U' R' F R2 U R' U' F'"),
+    ("OLL 10", "R U R' U R' F R F' R U2 R'"),
+    ("OLL 11", "r U R' U R' F R F' R U2 r'"),
+    ("OLL 12", "M' R' U' R U' R' U2 R U' M"),
+    ("OLL 13", "F U R U' R2 F' R U R U' R'"),
+    ("OLL 14", "R' F R U R' F' R F U' F'"),
+    ("OLL 15", "r' U' r R' U' R U r' U r"),
+    ("OLL 16", "r U r' R U R' U' r U' r'"),
+    ("OLL 17", "R U R' U R' F R F' U2 R' F R F'"),
+    ("OLL 18", "r U R' U R U2 r2 U' R U' R' U2 r"),
+    ("OLL 19", "M U R U R' U' M' R' F R F'"),
+    ("OLL 20", "r U R' U' M2 U R U' R' U' M'"),
+    ("OLL 21", "R U2 R' U' R U R' U' R U' R'"),
+    ("OLL 22", "R U2 R2 U' R2 U' R2 U2 R"),
+    ("OLL 23", "R2 D' R U2 R' D R U2 R"),
+    ("OLL 24", "r U R' U' r' F R F'"),
+    ("OLL 25", "F' r U R' U' r' F R"),
+    ("OLL 26", "R U2 R' U' R U' R'"),
+    ("OLL 27", "R U R' U R U2 R'"),
+    ("OLL 28", "r U R' U' r' R U R U' R'"),
+    ("OLL 29", "R U R' U' R U' R' F' U' F R U R'"),
+    ("OLL 30", "F R' F R2 U' R' U' R U R' F2"),
+    ("OLL 31", "R' U' F U R U' R' F' R"),
+    ("OLL 32", "L U F' U' L' U L F L'"),
+    ("OLL 33", "R U R' U' R' F R F'"),
+    ("OLL 34", "R U R2 U' R' F R U R U' F'"),
+    ("OLL 35", "R U2 R2 F R F' R U2 R'"),
+    ("OLL 36", "L' U' L U' L' U L U L F' L' F"),
+    ("OLL 37", "F R' F' R U R U' R'"),
+    ("OLL 38", "R U R' U R U' R' U' R' F R F'"),
+    ("OLL 39", "L F' L' U' L U F U' L'"),
+    ("OLL 40", "R' F R U R' U' F' U R"),
+    ("OLL 41", "R U R' U R U2 R' F R U R' U' F'"),
+    ("OLL 42", "R' U' R U' R' U2 R F R U R' U' F'"),
+    ("OLL 43", "F' U' L' U L F"),
+    ("OLL 44", "F U R U' R' F'"),
+    ("OLL 45", "F R U R' U' F'"),
+    ("OLL 46", "R' U' R' F R F' U R"),
+    ("OLL 47", "R' U' R' F R F' R' F R F' U R"),
+    ("OLL 48", "F R U R' U' R U R' U' F'"),
+    ("OLL 49", "r U' r2 U r2 U r2 U' r"),
+    ("OLL 50", "r' U r2 U' r2 U' r2 U r'"),
+    ("OLL 51", "F U R U' R' U R U' R' F'"),
+    ("OLL 52", "R U R' U R U' B U' B' R'"),
+    ("OLL 53", "l' U2 L U L' U' L U L' U l"),
+    ("OLL 54", "r U2 R' U' R U R' U' R U' r'"),
+    ("OLL 55", "R' F R U R U' R2 F' R2 U' R' U R U R'"),
+    ("OLL 56", "r' U' r U' R' U R U' R' U R r' U r"),
+    ("OLL 57", "R U R' U' M' U R U' r'"),
+];
+
+pub(crate) const PLL_CASES: [(&str, &str); 21] = [
+    ("Aa perm", "x R' U R' D2 R U' R' D2 R2 x'"),
+    ("Ab perm", "x R2 D2 R U R' D2 R U' R x'"),
+    ("E perm", "x' R U' R' D R U R' D' R U R' D R U' R' D' x"),
+    ("F perm", "R' U' F' R U R' U' R' F R2 U' R' U' R U R' U R"),
+    ("Ga perm", "R2 U R' U R' U' R U' R2 U' D R' U R D'"),
+    ("Gb perm", "R' U' R U D' R2 U R' U R U' R U' R2 D"),
+    ("Gc perm", "R2 U' R U' R U R' U R2 U D' R U' R' D"),
+    ("Gd perm", "R U R' U' D R2 U' R U' R' U R' U R2 D'"),
+    ("H perm", "M2 U M2 U2 M2 U M2"),
+    ("Ja perm", "R' U L' U2 R U' R' U2 R L"),
+    ("Jb perm", "R U R' F' R U R' U' R' F R2 U' R'"),
+    (
+        "Na perm",
+        "R U R' U R U R' F' R U R' U' R' F R2 U' R' U2 R U' R'",
+    ),
+    ("Nb perm", "R' U R U' R' F' U' F R U R' F R' F' R U' R"),
+    ("Ra perm", "R U' R' U' R U R D R' U' R D' R' U2 R'"),
+    ("Rb perm", "R2 F R U R U' R' F' R U2 R' U2 R"),
+    ("T perm", "R U R' U' R' F R2 U' R' U' R U R' F'"),
+    ("Ua perm", "M2 U M U2 M' U M2"),
+    ("Ub perm", "M2 U' M U2 M' U' M2"),
+    ("V perm", "R U' R U R' D R D' R U' D R2 U R2 D' R2"),
+    ("Y perm", "F R U' R' U' R U R' F' R U R' U' R' F R F'"),
+    ("Z perm", "M' U M2 U M2 U M' U2 M2"),
+];
+
+// The algorithm solving the front right pair from one place of its corner
+// and edge, keeping the rest of the first two layers.
+struct F2lCase {
+    description: String,
+    algorithm: String,
+}
+
+#[derive(PartialEq, Debug, Clone, Copy, Default)]
+pub struct CfopSolver {}
+
+impl CfopSolver {
+    pub fn new() -> CfopSolver {
+        CfopSolver {}
+    }
+
+    // Solves the cross, the four pairs of the first two layers, then the
+    // last layer with one orientation and one permutation algorithm.
+    pub fn solve(&self, cube: &RubiksCube) -> Result<Solution, SolveError> {
+        let mut solving = SolvingCube::new(cube)?;
+        solve_cross(&mut solving);
+        let mut done = [false; 4];
+        for pair in 1..=4 {
+            solve_pair(&mut solving, pair, &mut done);
+        }
+        let (name, algorithm) = oll_case(&solving.cube).unwrap();
+        solving.apply(&algorithm);
+        solving.finish_stage("OLL", name.to_string());
+        let (name, algorithm) = pll_case(&solving.cube).unwrap();
+        solving.apply(&algorithm);
+        solving.finish_stage("PLL", name.to_string());
+        Ok(solving.into_solution())
+    }
+
+    // The name of the orientation case of the last layer, from "OLL 1" to
+    // "OLL 57" or "OLL skip", if the first two layers are solved.
+    pub fn recognize_oll(&self, cube: &RubiksCube) -> Option<&'static str> {
+        let solving = SolvingCube::new(cube).ok()?;
+        oll_case(&solving.cube).map(|(name, _)| name)
+    }
+
+    // The name of the permutation case of the last layer, like "T perm" or
+    // "PLL skip", if the last layer is already oriented.
+    pub fn recognize_pll(&self, cube: &RubiksCube) -> Option<&'static str> {
+        let solving = SolvingCube::new(cube).ok()?;
+        pll_case(&solving.cube).map(|(name, _)| name)
+    }
+}
+
+fn first_two_layers_solved(cube: &CubieCube) -> bool {
+    (4..8).all(|c| corner_solved(cube, c)) && (4..12).all(|e| edge_solved(cube, e))
+}
+
+fn last_layer_oriented(cube: &CubieCube) -> bool {
+    (0..4).all(|p| cube.corner_orientation[p] == 0 && cube.edge_orientation[p] == 0)
+}
+
+// The case and the algorithm solving it, preceded by the turn of the up face
+// that puts the case in front.
+fn oll_case(cube: &CubieCube) -> Option<(&'static str, String)> {
+    if !first_two_layers_solved(cube) {
+        return None;
+    }
+    if last_layer_oriented(cube) {
+        return Some(("OLL skip", String::new()));
+    }
+    OLL_CASES.iter().find_map(|(name, algorithm)| {
+        UP_TURNS
+            .iter()
+            .map(|turn| format!("{} {}", turn, algorithm))
+            .find(|candidate| last_layer_oriented(&cube.apply(&candidate.parse().unwrap())))
+            .map(|candidate| (*name, candidate))
+    })
+}
+
+// Like `oll_case`, with a last turn of the up face to match the sides.
+fn pll_case(cube: &CubieCube) -> Option<(&'static str, String)> {
+    if !first_two_layers_solved(cube) || !last_layer_oriented(cube) {
+        return None;
+    }
+    let solves = |algorithm: &str| cube.apply(&algorithm.parse().unwrap()) == CubieCube::new();
+    if let Some(turn) = UP_TURNS.iter().find(|turn| solves(turn)) {
+        return Some(("PLL skip", turn.to_string()));
+    }
+    PLL_CASES.iter().find_map(|(name, algorithm)| {
+        (0..16)
+            .map(|i| format!("{} {} {}", UP_TURNS[i / 4], algorithm, UP_TURNS[i % 4]))
+            .find(|candidate| solves(candidate))
+            .map(|candidate| (*name, candidate))
+    })
+}
+
+// Of the pairs left, solves the one with the shortest algorithm, or names
+// one already solved. Pieces of the pair stuck in another slot are taken out
+// of it first.
+fn solve_pair(solving: &mut SolvingCube, pair: usize, done: &mut [bool; 4]) {
+    let slot_solved = |cube: &CubieCube, slot: usize| {
+        corner_solved(cube, slot + 4) && edge_solved(cube, slot + 8)
+    };
+    let left = (0..4).filter(|slot| !done[*slot]);
+    let name = |slot: usize| format!("{} pair", solving.corner_name(slot + 4));
+    if let Some(slot) = left.clone().find(|slot| slot_solved(&solving.cube, *slot)) {
+        done[slot] = true;
+        let description = format!("{}, already solved", name(slot));
+        solving.finish_stage(&format!("F2L {}", pair), description);
+        return;
+    }
+    let (slot, setup, algorithm, case) = left
+        .map(|slot| {
+            let (setup, algorithm, case) = pair_solution(&solving.cube, slot);
+            (slot, setup, algorithm, case)
+        })
+        .min_by_key(|(_, setup, algorithm, _)| setup.len() + algorithm.len())
+        .unwrap();
+    done[slot] = true;
+    let mut description = format!("{}, {}", name(slot), case);
+    if !setup.is_empty() {
+        description.push_str(", taken out of another slot first");
+    }
+    solving.apply_algorithm(&setup);
+    solving.apply_algorithm(&algorithm);
+    solving.finish_stage(&format!("F2L {}", pair), description);
+}
+
+// The moves taking the pieces of the slot out of other slots, then the ones
+// solving the case they are left in, with its description.
+fn pair_solution(cube: &CubieCube, slot: usize) -> (Algorithm, Algorithm, &'static str) {
+    let side = SLOT_SIDES[slot];
+    let mut cube = *cube;
+    let mut setup = Algorithm::default();
+    loop {
+        let corner = corner_position(&cube, slot + 4);
+        let edge = edge_position(&cube, slot + 8);
+        let other = if corner >= 4 && corner != slot + 4 {
+            corner - 4
+        } else if edge >= 8 && edge != slot + 8 {
+            edge - 8
+        } else {
+            break;
+        };
+        let extract = from_side(SLOT_SIDES[other], EXTRACT);
+        cube = cube.apply(&extract);
+        setup.moves.extend(extract.moves);
+    }
+    f2l_cases()
+        .iter()
+        .map(|case| (from_side(side, &case.algorithm), case.description.as_str()))
+        .find(|(algorithm, _)| {
+            let solved = cube.apply(algorithm);
+            corner_solved(&solved, slot + 4) && edge_solved(&solved, slot + 8)
+        })
+        .map(|(algorithm, description)| (setup, algorithm, description))
+        .unwrap()
+}
+
+// Every place of the front right corner and edge out of the other slots,
+// solved once by a search over `F2L_MOVES` for the shortest algorithm.
+fn f2l_cases() -> &'static Vec<F2lCase> {
+    static CASES: OnceLock<Vec<F2lCase>> = OnceLock::new();
+    CASES.get_or_init(|| {
+        // The pieces of the first two layers the moves can reach, with the
+        // front right pair, all home once the case is solved.
+        let tables = [
+            PatternDatabase::new(PieceKind::Edge, vec![4, 5, 8, 9, 11], &F2L_MOVES),
+            PatternDatabase::new(PieceKind::Corner, vec![4, 5, 7], &F2L_MOVES),
+        ];
+        let mut cases = Vec::new();
+        for corner in [0, 1, 2, 3, 4] {
+            for twist in 0..3 {
+                for edge in [0, 1, 2, 3, 8] {
+                    for flip in 0..2 {
+                        if corner == 4 && twist == 0 && edge == 8 && flip == 0 {
+                            continue;
+                        }
+                        let cube = f2l_case_cube(corner, twist as u8, edge, flip as u8);
+                        let mut moves = Vec::new();
+                        let mut depth = lower_bound(&tables, &cube);
+                        while !search(&tables, &cube, &mut moves, depth) {
+                            depth += 1;
+                        }
+                        let moves: Vec<Move> = moves.into_iter().map(Move::face_turn).collect();
+                        cases.push(F2lCase {
+                            description: f2l_description(corner, twist, edge, flip),
+                            algorithm: Algorithm::new(moves).to_string(),
+                        });
+                    }
+                }
+            }
+        }
+        cases
+    })
+}
+
+// A solvable cube with the front right corner and edge at the given places
+// and the rest of the first two layers solved. Pieces of the up layer are
+// swapped and turned to keep it solvable.
+fn f2l_case_cube(corner: usize, twist: u8, edge: usize, flip: u8) -> CubieCube {
+    let mut cube = CubieCube::new();
+    cube.corner_permutation.swap(corner, 4);
+    cube.edge_permutation.swap(edge, 8);
+    let other_corner = if corner == 0 { 1 } else { 0 };
+    let other_edges: Vec<usize> = (0..4).filter(|e| *e != edge).collect();
+    if (corner == 4) != (edge == 8) {
+        cube.edge_permutation.swap(other_edges[0], other_edges[1]);
+    }
+    cube.corner_orientation[corner] = twist;
+    cube.corner_orientation[other_corner] = (3 - twist) % 3;
+    cube.edge_orientation[edge] = flip;
+    cube.edge_orientation[other_edges[0]] = flip;
+    cube
+}
+
+fn f2l_description(corner: usize, twist: usize, edge: usize, flip: usize) -> String {
+    let corner_place = match corner {
+        4 => "in the slot".to_string(),
+        _ => format!("at the {} of the up layer", CORNER_PLACES[corner]),
+    };
+    let edge_place = match edge {
+        8 => "in the slot".to_string(),
+        _ => format!("at the {} of the up layer", EDGE_PLACES[edge]),
+    };
+    format!(
+        "corner {}, {}; edge {}, {}",
+        corner_place, TWISTS[twist], edge_place, FLIPS[flip]
+    )
+}
+
+fn lower_bound(tables: &[PatternDatabase; 2], cube: &CubieCube) -> usize {
+    tables.iter().map(|table| table.depth(cube)).max().unwrap() as usize
+}
+
+// Depth first search for `togo` moves or less bringing every table to 0,
+// leaving them in `moves`.
+fn search(
+    tables: &[PatternDatabase; 2],
+    cube: &CubieCube,
+    moves: &mut Vec<usize>,
+    togo: usize,
+) -> bool {
+    let bound = lower_bound(tables, cube);
+    if bound == 0 {
+        return true;
+    }
+    if bound > togo {
+        return false;
+    }
+    for m in F2L_MOVES.iter() {
+        if moves.last().is_some_and(|last| last / 3 == m / 3) {
+            continue;
+        }
+        moves.push(*m);
+        if search(
+            tables,
+            &cube.apply_move(Move::face_turn(*m)),
+            moves,
+            togo - 1,
+        ) {
+            return true;
+        }
+        moves.pop();
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solve_should_solve_scrambles_in_named_stages() {
+        let solver = CfopSolver::new();
+        let scrambles = [
+            "R U R' U'",
+            "F2 D' L B2 R' U2 F' D2 L2 B R2 U' F L' D B' R U2 L F'",
+            "U R2 F B R B2 R U2 L B2 R U' D' R2 F R' L B2 U2 F2",
+            "M' E S2 r U2 x y' F' D",
+            "L2 D B' U R2 F' L' D2 B U' R' F2 D L B2 U2 R D' F L'",
+        ];
+        for scramble in scrambles.iter() {
+            let cube = RubiksCube::new().apply(&scramble.parse().unwrap());
+            let solution = solver.solve(&cube).unwrap();
+            assert!(cube.apply(&solution.algorithm()).is_solved());
+            let names: Vec<&str> = solution.stages.iter().map(|s| s.name.as_str()).collect();
+            assert_eq!(
+                names,
+                vec!["cross", "F2L 1", "F2L 2", "F2L 3", "F2L 4", "OLL", "PLL"]
+            );
+        }
+    }
+
+    #[test]
+    fn test_recognize_oll_should_name_every_case() {
+        let solver = CfopSolver::new();
+        for (name, algorithm) in OLL_CASES.iter() {
+            for turn in UP_TURNS.iter() {
+                let cube = RubiksCube::new()
+                    .apply(&algorithm.parse::<Algorithm>().unwrap().inverse())
+                    .apply(&turn.parse().unwrap());
+                assert_eq!(solver.recognize_oll(&cube), Some(*name));
+                assert_eq!(
+                    solver
+                        .solve(&cube)
+                        .unwrap()
+                        .stage("OLL")
+                        .unwrap()
+                        .description,
+                    *name
+                );
+            }
+        }
+        assert_eq!(solver.recognize_oll(&RubiksCube::new()), Some("OLL skip"));
+        let cube = RubiksCube::new().apply(&"R U R'".parse().unwrap());
+        assert_eq!(solver.recognize_oll(&cube), None);
+    }
+
+    #[test]
+    fn test_recognize_pll_should_name_every_case() {
+        let solver = CfopSolver::new();
+        for (name, algorithm) in PLL_CASES.iter() {
+            for turn in UP_TURNS.iter() {
+                let cube = RubiksCube::new()
+                    .apply(&algorithm.parse::<Algorithm>().unwrap().inverse())
+                    .apply(&turn.parse().unwrap());
+                assert_eq!(solver.recognize_pll(&cube), Some(*name));
+                let solution = solver.solve(&cube).unwrap();
+                assert!(cube.apply(&solution.algorithm()).is_solved());
+            }
+        }
+        let cube = RubiksCube::new().apply(&"U2".parse().unwrap());
+        assert_eq!(solver.recognize_pll(&cube), Some("PLL skip"));
+        let cube = RubiksCube::new().apply(&"F R U R' U' F'".parse().unwrap());
+        assert_eq!(solver.recognize_pll(&cube), None);
+    }
+
+    #[test]
+    fn test_f2l_cases_should_cover_every_place_of_the_pair() {
+        assert_eq!(f2l_cases().len(), 149);
+        let cube = RubiksCube::new().apply(&"R U' R' U2 F' U F".parse().unwrap());
+        let solution = CfopSolver::new().solve(&cube).unwrap();
+        assert!(solution.stage("F2L 1").unwrap().moves.len() <= 7);
+        assert!(solution.stage("cross").unwrap().moves.is_empty());
+    }
+}
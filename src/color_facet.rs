@@ -1,3 +1,4 @@
+use crate::Axis;
 use std::slice::Iter;
 
 #[derive(PartialEq, Clone, Copy)]
@@ -22,6 +23,61 @@ impl ColorFacet {
         ];
         COLOR_FACET.iter()
     }
+
+    pub fn from_notation(letter: char) -> Option<ColorFacet> {
+        match letter {
+            'U' => Some(ColorFacet::White),
+            'D' => Some(ColorFacet::Yellow),
+            'L' => Some(ColorFacet::Green),
+            'R' => Some(ColorFacet::Blue),
+            'F' => Some(ColorFacet::Red),
+            'B' => Some(ColorFacet::Orange),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ColorFacet::Red => "red",
+            ColorFacet::Blue => "blue",
+            ColorFacet::Green => "green",
+            ColorFacet::Orange => "orange",
+            ColorFacet::White => "white",
+            ColorFacet::Yellow => "yellow",
+        }
+    }
+
+    // The color of the face across the cube from this one.
+    pub fn opposite(self) -> ColorFacet {
+        match self {
+            ColorFacet::Red => ColorFacet::Orange,
+            ColorFacet::Orange => ColorFacet::Red,
+            ColorFacet::Blue => ColorFacet::Green,
+            ColorFacet::Green => ColorFacet::Blue,
+            ColorFacet::White => ColorFacet::Yellow,
+            ColorFacet::Yellow => ColorFacet::White,
+        }
+    }
+
+    // The axis the face is turned around, as in cube rotations.
+    pub fn axis(self) -> Axis {
+        match self {
+            ColorFacet::Blue | ColorFacet::Green => Axis::X,
+            ColorFacet::White | ColorFacet::Yellow => Axis::Y,
+            ColorFacet::Red | ColorFacet::Orange => Axis::Z,
+        }
+    }
+
+    pub fn notation(self) -> char {
+        match self {
+            ColorFacet::White => 'U',
+            ColorFacet::Yellow => 'D',
+            ColorFacet::Green => 'L',
+            ColorFacet::Blue => 'R',
+            ColorFacet::Red => 'F',
+            ColorFacet::Orange => 'B',
+        }
+    }
 }
 
 impl std::fmt::Debug for ColorFacet {
@@ -0,0 +1,55 @@
+use crate::ColorFacet;
+
+// Which color sits on each face of a facelet string, read in the up, right,
+// front, down, left, back order.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct ColorScheme {
+    pub up: ColorFacet,
+    pub right: ColorFacet,
+    pub front: ColorFacet,
+    pub down: ColorFacet,
+    pub left: ColorFacet,
+    pub back: ColorFacet,
+}
+
+const LETTERS: [char; 6] = ['U', 'R', 'F', 'D', 'L', 'B'];
+
+impl Default for ColorScheme {
+    fn default() -> Self {
+        ColorScheme {
+            up: ColorFacet::White,
+            right: ColorFacet::Blue,
+            front: ColorFacet::Red,
+            down: ColorFacet::Yellow,
+            left: ColorFacet::Green,
+            back: ColorFacet::Orange,
+        }
+    }
+}
+
+impl ColorScheme {
+    pub fn colors(&self) -> [ColorFacet; 6] {
+        [
+            self.up, self.right, self.front, self.down, self.left, self.back,
+        ]
+    }
+
+    pub fn is_valid(&self) -> bool {
+        let colors = self.colors();
+        (0..6).all(|i| !colors[i + 1..].contains(&colors[i]))
+    }
+
+    pub fn color(&self, letter: char) -> Option<ColorFacet> {
+        LETTERS
+            .iter()
+            .position(|l| *l == letter)
+            .map(|i| self.colors()[i])
+    }
+
+    pub fn letter(&self, color: ColorFacet) -> Option<char> {
+        self.colors()
+            .iter()
+            .position(|c| *c == color)
+            .map(|i| LETTERS[i])
+    }
+}
@@ -0,0 +1,232 @@
+use crate::two_phase_tables::{CORNERS, FLIPS, TWISTS};
+use crate::CubieCube;
+use crate::RubiksCube;
+use crate::ValidationError;
+use std::convert::TryFrom;
+
+// Orders of all twelve edges, and ways to hold the cube.
+const EDGES: u128 = 479001600;
+const ORIENTATIONS: u128 = 24;
+
+// A cube state packed in 9 bytes: how the cube is held, then the order and
+// twists of the corners, then the order and flips of the edges, as one
+// number written most significant byte first. Comparing the bytes compares
+// the numbers, which gives the order of the states.
+#[repr(transparent)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Clone, Copy)]
+pub struct CompactCube {
+    bytes: [u8; CompactCube::BYTES],
+}
+
+impl Default for CompactCube {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TryFrom<CubieCube> for CompactCube {
+    type Error = ValidationError;
+
+    fn try_from(cube: CubieCube) -> Result<Self, Self::Error> {
+        cube.validate()?;
+        Ok(CompactCube::from_parts(0, &cube))
+    }
+}
+
+impl CompactCube {
+    pub const BYTES: usize = 9;
+
+    pub fn new() -> CompactCube {
+        CompactCube::from_parts(0, &CubieCube::new())
+    }
+
+    // Keeps how the cube is held along with its cubies, but not which facet
+    // of a color is where: facets get the indexes of a solved cube back.
+    pub fn from_rubiks_cube(cube: &RubiksCube) -> Result<CompactCube, ValidationError> {
+        let rotations = cube.orientation().ok_or(ValidationError::InvalidCenters)?;
+        let orientation = RubiksCube::orientations()
+            .iter()
+            .position(|o| *o == rotations)
+            .unwrap();
+        let cubie_cube = CubieCube::from_rubiks_cube(cube)?;
+        cubie_cube.validate()?;
+        Ok(CompactCube::from_parts(orientation, &cubie_cube))
+    }
+
+    pub fn to_rubiks_cube(&self) -> RubiksCube {
+        let orientation = &RubiksCube::orientations()[self.orientation()];
+        self.to_cubie_cube().to_rubiks_cube_oriented(orientation)
+    }
+
+    pub fn to_cubie_cube(&self) -> CubieCube {
+        let mut rest = self.number();
+        let mut next = |size: u128| {
+            let part = rest % size;
+            rest /= size;
+            part as usize
+        };
+        let flip = next(FLIPS as u128);
+        let edges = next(EDGES);
+        let twist = next(TWISTS as u128);
+        let corners = next(CORNERS as u128);
+        CubieCube::new()
+            .with_corners(corners)
+            .with_twist(twist)
+            .with_edges(edges)
+            .with_flip(flip)
+    }
+
+    // Which of `RubiksCube::orientations` the cube is held in.
+    pub fn orientation(&self) -> usize {
+        (self.number() / (CORNERS as u128 * TWISTS as u128 * EDGES * FLIPS as u128)) as usize
+    }
+
+    pub fn to_bytes(&self) -> [u8; CompactCube::BYTES] {
+        self.bytes
+    }
+
+    // None for bytes past the number of the last state, or giving corners
+    // and edges no turns of a cube can reach.
+    pub fn from_bytes(bytes: [u8; CompactCube::BYTES]) -> Option<CompactCube> {
+        let cube = CompactCube { bytes };
+        if cube.orientation() < ORIENTATIONS as usize && cube.to_cubie_cube().validate().is_ok() {
+            Some(cube)
+        } else {
+            None
+        }
+    }
+
+    // Reads cubes stored one after another without copying them. None when
+    // the length is not a whole number of cubes or one of them is invalid.
+    pub fn slice_from_bytes(bytes: &[u8]) -> Option<&[CompactCube]> {
+        if bytes.len() % CompactCube::BYTES != 0 {
+            return None;
+        }
+        // SAFETY: `CompactCube` is a transparent wrapper around bytes, so it
+        // has the size of its bytes and an alignment of 1.
+        let cubes = unsafe {
+            std::slice::from_raw_parts(
+                bytes.as_ptr() as *const CompactCube,
+                bytes.len() / CompactCube::BYTES,
+            )
+        };
+        if cubes
+            .iter()
+            .all(|cube| CompactCube::from_bytes(cube.bytes).is_some())
+        {
+            Some(cubes)
+        } else {
+            None
+        }
+    }
+
+    // The bytes of cubes stored one after another, without copying them.
+    pub fn slice_as_bytes(cubes: &[CompactCube]) -> &[u8] {
+        // SAFETY: as in `slice_from_bytes`, every cube is exactly its bytes.
+        unsafe {
+            std::slice::from_raw_parts(
+                cubes.as_ptr() as *const u8,
+                cubes.len() * CompactCube::BYTES,
+            )
+        }
+    }
+
+    fn from_parts(orientation: usize, cube: &CubieCube) -> CompactCube {
+        let parts = [
+            (cube.corners(), CORNERS as u128),
+            (cube.twist(), TWISTS as u128),
+            (cube.edges(), EDGES),
+            (cube.flip(), FLIPS as u128),
+        ];
+        let number = parts
+            .iter()
+            .fold(orientation as u128, |number, (part, size)| {
+                number * size + *part as u128
+            });
+        let mut bytes = [0; CompactCube::BYTES];
+        bytes.copy_from_slice(&number.to_be_bytes()[16 - CompactCube::BYTES..]);
+        CompactCube { bytes }
+    }
+
+    fn number(&self) -> u128 {
+        let mut bytes = [0; 16];
+        bytes[16 - CompactCube::BYTES..].copy_from_slice(&self.bytes);
+        u128::from_be_bytes(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    const SCRAMBLES: [&str; 4] = [
+        "",
+        "F2 D' L B2 R' U2 F' D2 L2 B R2 U' F L' D B' R U2 L F'",
+        "M' E S2 r U2 x y' F' D",
+        "x2 y",
+    ];
+
+    #[test]
+    fn test_from_rubiks_cube_should_round_trip() {
+        for scramble in SCRAMBLES.iter() {
+            let cube = RubiksCube::new().apply(&scramble.parse().unwrap());
+            let compact = CompactCube::from_rubiks_cube(&cube).unwrap();
+            assert_eq!(compact.to_rubiks_cube(), cube, "{}", scramble);
+            assert_eq!(CompactCube::from_bytes(compact.to_bytes()), Some(compact));
+            let cubie_cube = CubieCube::from_rubiks_cube(&cube).unwrap();
+            assert_eq!(compact.to_cubie_cube(), cubie_cube);
+        }
+        assert_eq!(CompactCube::from_bytes([255; CompactCube::BYTES]), None);
+    }
+
+    #[test]
+    fn test_order_should_follow_the_bytes() {
+        let mut cubes: Vec<CompactCube> = SCRAMBLES
+            .iter()
+            .map(|s| {
+                let cube = RubiksCube::new().apply(&s.parse().unwrap());
+                CompactCube::from_rubiks_cube(&cube).unwrap()
+            })
+            .collect();
+        let distinct: HashSet<CompactCube> = cubes.iter().cloned().collect();
+        assert_eq!(distinct.len(), SCRAMBLES.len());
+        cubes.sort();
+        assert_eq!(cubes[0], CompactCube::new());
+        assert!(cubes
+            .windows(2)
+            .all(|pair| pair[0].to_bytes() < pair[1].to_bytes()));
+    }
+
+    #[test]
+    fn test_slices_should_share_the_bytes() {
+        let cubes = [
+            CompactCube::new(),
+            CompactCube::try_from(CubieCube::new().apply(&"R U".parse().unwrap())).unwrap(),
+        ];
+        let bytes = CompactCube::slice_as_bytes(&cubes);
+        assert_eq!(bytes.len(), 2 * CompactCube::BYTES);
+        assert_eq!(CompactCube::slice_from_bytes(bytes), Some(&cubes[..]));
+        assert_eq!(CompactCube::slice_from_bytes(&bytes[1..]), None);
+        let mut swapped = bytes.to_vec();
+        swapped[CompactCube::BYTES - 2] = 1 << 3;
+        assert_eq!(CompactCube::slice_from_bytes(&swapped), None);
+    }
+
+    #[test]
+    fn test_unsolvable_states_should_be_refused() {
+        // Edges numbered 1 in their order, two of them swapped.
+        let mut bytes = [0; CompactCube::BYTES];
+        bytes[CompactCube::BYTES - 2] = 1 << 3;
+        assert_eq!(CompactCube::from_bytes(bytes), None);
+        let mut twisted = CubieCube::new();
+        twisted.corner_orientation[0] = 1;
+        assert_eq!(
+            CompactCube::try_from(twisted),
+            Err(ValidationError::TwistedCorner)
+        );
+        let mut duplicated = CubieCube::new();
+        duplicated.edge_permutation[1] = duplicated.edge_permutation[0];
+        assert!(CompactCube::try_from(duplicated).is_err());
+    }
+}
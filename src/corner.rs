@@ -0,0 +1,34 @@
+use std::slice::Iter;
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Corner {
+    Urf,
+    Ufl,
+    Ulb,
+    Ubr,
+    Dfr,
+    Dlf,
+    Dbl,
+    Drb,
+}
+
+static CORNER: [Corner; 8] = [
+    Corner::Urf,
+    Corner::Ufl,
+    Corner::Ulb,
+    Corner::Ubr,
+    Corner::Dfr,
+    Corner::Dlf,
+    Corner::Dbl,
+    Corner::Drb,
+];
+
+impl Corner {
+    pub fn iterator() -> Iter<'static, Corner> {
+        CORNER.iter()
+    }
+
+    pub fn from_index(index: usize) -> Corner {
+        CORNER[index]
+    }
+}
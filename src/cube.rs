@@ -0,0 +1,272 @@
+use crate::Algorithm;
+use crate::Axis;
+use crate::ColorFacet;
+use crate::Face;
+use crate::FaceGrid;
+use crate::Facet;
+use crate::Move;
+use crate::RotationDirection;
+use crate::RubiksCube;
+
+// A cube with N layers along each axis. Faces are kept in the order of
+// `ColorFacet::iterator`, each named by the color it has when solved, as the
+// fields of `RubiksCube` are.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct Cube<const N: usize> {
+    pub faces: [FaceGrid<N>; 6],
+}
+
+impl<const N: usize> Default for Cube<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> Cube<N> {
+    pub fn new() -> Cube<N> {
+        let mut faces = [FaceGrid::new(ColorFacet::Red); 6];
+        for (face, color) in faces.iter_mut().zip(ColorFacet::iterator()) {
+            *face = FaceGrid::new(*color);
+        }
+        Cube { faces }
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.faces.iter().all(|face| face.is_one_color())
+    }
+
+    pub fn face(&self, face: ColorFacet) -> FaceGrid<N> {
+        self.faces[face_index(face)]
+    }
+
+    pub fn with_face(self, face: ColorFacet, content: FaceGrid<N>) -> Cube<N> {
+        let mut cube = self;
+        cube.faces[face_index(face)] = content;
+        cube
+    }
+
+    // Turns the layer `depth` layers in from the face, clockwise as seen
+    // from that face. Depth 0 is the face itself.
+    pub fn turn(self, face: ColorFacet, depth: usize, direction: RotationDirection) -> Cube<N> {
+        self.turn_layers(face, depth..depth + 1, direction)
+    }
+
+    // Turns the face together with the layers behind it, `layers` in all.
+    pub fn turn_wide(
+        self,
+        face: ColorFacet,
+        layers: usize,
+        direction: RotationDirection,
+    ) -> Cube<N> {
+        self.turn_layers(face, 0..layers, direction)
+    }
+
+    pub fn rotate(self, face: ColorFacet, direction: RotationDirection) -> Cube<N> {
+        self.turn(face, 0, direction)
+    }
+
+    pub fn rotate_cube(self, axis: Axis, direction: RotationDirection) -> Cube<N> {
+        self.turn_wide(axis.face(), N, direction)
+    }
+
+    pub fn apply(self, algorithm: &Algorithm) -> Cube<N> {
+        algorithm
+            .moves
+            .iter()
+            .fold(self, |cube, cube_move| cube.apply_move(*cube_move))
+    }
+
+    // Slice moves turn every inner layer, following the left, down or front
+    // face, and wide moves the two outer layers, as on a 3x3.
+    pub fn apply_move(self, cube_move: Move) -> Cube<N> {
+        match cube_move {
+            Move::Face(face, direction) => self.rotate(face, direction),
+            Move::Wide(face, direction) => self.turn_wide(face, 2, direction),
+            Move::Rotation(axis, direction) => self.rotate_cube(axis, direction),
+            Move::Slice(slice, direction) => self.turn_layers(slice.face(), 1..N - 1, direction),
+        }
+    }
+
+    fn turn_layers(
+        self,
+        face: ColorFacet,
+        depths: std::ops::Range<usize>,
+        direction: RotationDirection,
+    ) -> Cube<N> {
+        let axis = normal(face);
+        let size = N as i32 - 1;
+        let mut cube = self;
+        for (from, content) in ColorFacet::iterator().zip(self.faces.iter()) {
+            for (row, line) in content.facets.iter().enumerate() {
+                for (column, facet) in line.iter().enumerate() {
+                    let mut position = position(*from, row, column, N);
+                    let depth = (size - dot(axis, position)) / 2;
+                    if !depths.contains(&(depth as usize)) {
+                        continue;
+                    }
+                    let mut facing = normal(*from);
+                    for _ in 0..direction.quarter_turns() {
+                        position = turn_clockwise(axis, position);
+                        facing = turn_clockwise(axis, facing);
+                    }
+                    let to = ColorFacet::iterator()
+                        .find(|c| normal(**c) == facing)
+                        .unwrap();
+                    let (right, down) = directions(*to);
+                    let row = ((dot(down, position) + size) / 2) as usize;
+                    let column = ((dot(right, position) + size) / 2) as usize;
+                    cube.faces[face_index(*to)].facets[row][column] = *facet;
+                }
+            }
+        }
+        cube
+    }
+}
+
+impl From<RubiksCube> for Cube<3> {
+    fn from(cube: RubiksCube) -> Cube<3> {
+        ColorFacet::iterator().fold(Cube::new(), |result, color| {
+            let facets = cube.face(*color).facets();
+            let mut grid = FaceGrid::new(*color);
+            for (i, facet) in facets.iter().enumerate() {
+                grid.facets[i / 3][i % 3] = *facet;
+            }
+            result.with_face(*color, grid)
+        })
+    }
+}
+
+impl From<Cube<3>> for RubiksCube {
+    fn from(cube: Cube<3>) -> RubiksCube {
+        ColorFacet::iterator().fold(RubiksCube::new(), |result, color| {
+            let rows = cube.face(*color).facets;
+            let mut facets = [Facet::new(*color, 0); 9];
+            for (i, facet) in facets.iter_mut().enumerate() {
+                *facet = rows[i / 3][i % 3];
+            }
+            result.with_face(*color, Face::from_facets(facets))
+        })
+    }
+}
+
+pub(crate) fn face_index(face: ColorFacet) -> usize {
+    ColorFacet::iterator().position(|c| *c == face).unwrap()
+}
+
+// Positions are vectors from the center of the cube to the center of a
+// facet, in half facets, with x to the right face, y to the up face and z to
+// the front face, as the cube is held at home.
+fn normal(face: ColorFacet) -> [i32; 3] {
+    match face {
+        ColorFacet::Blue => [1, 0, 0],
+        ColorFacet::Green => [-1, 0, 0],
+        ColorFacet::White => [0, 1, 0],
+        ColorFacet::Yellow => [0, -1, 0],
+        ColorFacet::Red => [0, 0, 1],
+        ColorFacet::Orange => [0, 0, -1],
+    }
+}
+
+// The directions columns and rows go in on the face, as laid out in the net.
+fn directions(face: ColorFacet) -> ([i32; 3], [i32; 3]) {
+    match face {
+        ColorFacet::White => ([1, 0, 0], [0, 0, 1]),
+        ColorFacet::Yellow => ([1, 0, 0], [0, 0, -1]),
+        ColorFacet::Red => ([1, 0, 0], [0, -1, 0]),
+        ColorFacet::Orange => ([-1, 0, 0], [0, -1, 0]),
+        ColorFacet::Blue => ([0, 0, -1], [0, -1, 0]),
+        ColorFacet::Green => ([0, 0, 1], [0, -1, 0]),
+    }
+}
+
+pub(crate) fn position(face: ColorFacet, row: usize, column: usize, size: usize) -> [i32; 3] {
+    let size = size as i32 - 1;
+    let (right, down) = directions(face);
+    let column = 2 * column as i32 - size;
+    let row = 2 * row as i32 - size;
+    let mut position = normal(face);
+    for (i, coordinate) in position.iter_mut().enumerate() {
+        *coordinate = *coordinate * size + right[i] * column + down[i] * row;
+    }
+    position
+}
+
+fn dot(a: [i32; 3], b: [i32; 3]) -> i32 {
+    a.iter().zip(b.iter()).map(|(x, y)| x * y).sum()
+}
+
+// A quarter turn clockwise as seen looking at the cube from the axis, that
+// is a (a . v) - a x v for a unit axis a.
+fn turn_clockwise(axis: [i32; 3], v: [i32; 3]) -> [i32; 3] {
+    let cross = [
+        axis[1] * v[2] - axis[2] * v[1],
+        axis[2] * v[0] - axis[0] * v[2],
+        axis[0] * v[1] - axis[1] * v[0],
+    ];
+    let along = dot(axis, v);
+    [
+        axis[0] * along - cross[0],
+        axis[1] * along - cross[1],
+        axis[2] * along - cross[2],
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_three_layer_cube_should_turn_like_rubiks_cube() {
+        let algorithms = [
+            "R U R' U' F2 D' L B2",
+            "M' E S2 M2 E' S",
+            "x y' z2 x' y z",
+            "r U2 l' d f' b2 u D",
+            "F2 D' L B2 R' U2 F' D2 L2 B R2 U' F L' D B' R U2 L F'",
+        ];
+        for text in algorithms.iter() {
+            let algorithm: Algorithm = text.parse().unwrap();
+            for cube_move in algorithm.moves.iter() {
+                let single = Algorithm::new(vec![*cube_move]);
+                let expected = Cube::<3>::from(RubiksCube::new().apply(&single));
+                assert_eq!(Cube::<3>::new().apply(&single), expected, "{}", cube_move);
+            }
+            let rubiks_cube = RubiksCube::new().apply(&algorithm);
+            let cube = Cube::<3>::new().apply(&algorithm);
+            assert_eq!(cube, Cube::from(rubiks_cube));
+            assert_eq!(RubiksCube::from(cube), rubiks_cube);
+        }
+    }
+
+    #[test]
+    fn test_turns_should_return_to_solved() {
+        let algorithm: Algorithm = "R U R' U' r2 u' Fw M".parse().unwrap();
+        let cube = Cube::<5>::new().apply(&algorithm);
+        assert!(!cube.is_solved());
+        assert!(cube.apply(&algorithm.inverse()).is_solved());
+        let sexy: Algorithm = "R U R' U'".parse().unwrap();
+        assert_eq!(Cube::<4>::new().apply(&sexy.repeat(6)), Cube::new());
+        assert_eq!(Cube::<2>::new().apply(&sexy.repeat(6)), Cube::new());
+        for depth in 0..4 {
+            let turned = (0..4).fold(Cube::<4>::new(), |cube, _| {
+                cube.turn(ColorFacet::Blue, depth, RotationDirection::Clockwise)
+            });
+            assert_eq!(turned, Cube::new());
+        }
+    }
+
+    #[test]
+    fn test_inner_turn_should_move_one_layer() {
+        let cube = Cube::<4>::new().turn(ColorFacet::White, 1, RotationDirection::Clockwise);
+        let front = cube.face(ColorFacet::Red);
+        assert!(front.facets[0].iter().all(|f| f.color == ColorFacet::Red));
+        assert!(front.facets[1].iter().all(|f| f.color == ColorFacet::Blue));
+        assert!(front.facets[2].iter().all(|f| f.color == ColorFacet::Red));
+        assert!(cube.face(ColorFacet::White).is_one_color());
+        let wide = Cube::<4>::new().turn_wide(ColorFacet::White, 2, RotationDirection::Clockwise);
+        let turns = Cube::<4>::new()
+            .turn(ColorFacet::White, 0, RotationDirection::Clockwise)
+            .turn(ColorFacet::White, 1, RotationDirection::Clockwise);
+        assert_eq!(wide, turns);
+    }
+}
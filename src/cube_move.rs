@@ -0,0 +1,180 @@
+use crate::cubie_cube::FACES;
+use crate::Algorithm;
+use crate::Axis;
+use crate::ColorFacet;
+use crate::ParseError;
+use crate::RotationDirection;
+use crate::RubiksCube;
+use crate::Slice;
+use std::str::FromStr;
+
+// Turns in the order used to number the 18 face turns.
+const TURNS: [RotationDirection; 3] = [
+    RotationDirection::Clockwise,
+    RotationDirection::HalfTurn,
+    RotationDirection::Anticlockwise,
+];
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Move {
+    Face(ColorFacet, RotationDirection),
+    Slice(Slice, RotationDirection),
+    Rotation(Axis, RotationDirection),
+    Wide(ColorFacet, RotationDirection),
+}
+
+impl std::fmt::Display for Move {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.letter())?;
+        if let Move::Wide(_, _) = self {
+            write!(f, "w")?;
+        }
+        match self.direction() {
+            RotationDirection::Clockwise => Ok(()),
+            RotationDirection::Anticlockwise => write!(f, "'"),
+            RotationDirection::HalfTurn => write!(f, "2"),
+        }
+    }
+}
+
+impl FromStr for Move {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let chars: Vec<char> = s.chars().collect();
+        match Move::parse(&chars, 0) {
+            Ok((cube_move, end)) if end == chars.len() => Ok(cube_move),
+            Ok(_) => Err(ParseError::new(0, s.to_string())),
+            Err(error) => Err(error),
+        }
+    }
+}
+
+impl Move {
+    pub fn direction(self) -> RotationDirection {
+        match self {
+            Move::Face(_, direction)
+            | Move::Slice(_, direction)
+            | Move::Rotation(_, direction)
+            | Move::Wide(_, direction) => direction,
+        }
+    }
+
+    pub fn inverse(self) -> Move {
+        self.with_direction(self.direction().opposite())
+    }
+
+    // The same layer turned the other way or further.
+    pub fn with_direction(self, direction: RotationDirection) -> Move {
+        match self {
+            Move::Face(face, _) => Move::Face(face, direction),
+            Move::Slice(slice, _) => Move::Slice(slice, direction),
+            Move::Rotation(axis, _) => Move::Rotation(axis, direction),
+            Move::Wide(face, _) => Move::Wide(face, direction),
+        }
+    }
+
+    // The move seen in a mirror across the axis. Turns of the layers the
+    // mirror swaps go to the other side, and every turn goes the other way
+    // except those around the axis, which the mirror turns around twice.
+    pub fn mirror(self, axis: Axis) -> Move {
+        let direction = self.direction().opposite();
+        match self {
+            Move::Face(face, _) if face.axis() == axis => Move::Face(face.opposite(), direction),
+            Move::Wide(face, _) if face.axis() == axis => Move::Wide(face.opposite(), direction),
+            Move::Slice(slice, _) if slice.axis() == axis => self,
+            Move::Rotation(rotation, _) if rotation == axis => self,
+            _ => self.with_direction(direction),
+        }
+    }
+
+    // Face turns numbered face * 3 + turn, with faces in the up, right,
+    // front, down, left, back order, as the solvers index their tables.
+    pub(crate) fn face_turn(index: usize) -> Move {
+        Move::Face(FACES[index / 3], TURNS[index % 3])
+    }
+
+    pub fn letter(self) -> char {
+        match self {
+            Move::Face(face, _) | Move::Wide(face, _) => face.notation(),
+            Move::Slice(slice, _) => slice.notation(),
+            Move::Rotation(axis, _) => axis.notation(),
+        }
+    }
+
+    pub fn from_letter(letter: char, direction: RotationDirection) -> Option<Move> {
+        if let Some(face) = ColorFacet::from_notation(letter) {
+            return Some(Move::Face(face, direction));
+        }
+        if let Some(face) = ColorFacet::from_notation(letter.to_ascii_uppercase()) {
+            return Some(Move::Wide(face, direction));
+        }
+        if let Some(slice) = Slice::from_notation(letter) {
+            return Some(Move::Slice(slice, direction));
+        }
+        Axis::from_notation(letter).map(|axis| Move::Rotation(axis, direction))
+    }
+
+    // Parses one move starting at `start` and returns it with the position
+    // right after its last character.
+    pub(crate) fn parse(chars: &[char], start: usize) -> Result<(Move, usize), ParseError> {
+        let error = || ParseError::new(start, token_at(chars, start));
+        let letter = *chars.get(start).ok_or_else(error)?;
+        let mut end = start + 1;
+        let wide = chars.get(end) == Some(&'w');
+        if wide {
+            end += 1;
+        }
+        let mut direction = RotationDirection::Clockwise;
+        if chars.get(end) == Some(&'2') {
+            direction = RotationDirection::HalfTurn;
+            end += 1;
+        }
+        if chars.get(end) == Some(&'\'') {
+            if direction == RotationDirection::Clockwise {
+                direction = RotationDirection::Anticlockwise;
+            }
+            end += 1;
+        }
+        let cube_move = match Move::from_letter(letter, direction) {
+            Some(Move::Face(face, direction)) if wide => Move::Wide(face, direction),
+            Some(cube_move) if !wide => cube_move,
+            _ => return Err(error()),
+        };
+        match chars.get(end) {
+            Some(next)
+                if !next.is_whitespace() && !starts_move(*next) && !GROUPING.contains(*next) =>
+            {
+                Err(error())
+            }
+            _ => Ok((cube_move, end)),
+        }
+    }
+}
+
+// The face turn doing what the face turn does once the cube is rotated, so
+// that rotating, turning and rotating back is that one turn.
+pub(crate) fn held_face_turn(rotations: &Algorithm, cube_move: Move) -> Move {
+    let rotated = RubiksCube::new()
+        .apply(rotations)
+        .apply_move(cube_move)
+        .apply(&rotations.inverse());
+    ColorFacet::iterator()
+        .map(|face| Move::Face(*face, cube_move.direction()))
+        .find(|m| RubiksCube::new().apply_move(*m) == rotated)
+        .unwrap()
+}
+
+// Characters of the bracket and repetition notation read by `Algorithm`.
+const GROUPING: &str = "[],:()";
+
+fn starts_move(letter: char) -> bool {
+    Move::from_letter(letter, RotationDirection::Clockwise).is_some()
+}
+
+pub(crate) fn token_at(chars: &[char], start: usize) -> String {
+    chars[start..]
+        .iter()
+        .take_while(|c| !c.is_whitespace())
+        .collect()
+}
@@ -0,0 +1,753 @@
+use crate::Algorithm;
+use crate::Axis;
+use crate::ColorFacet;
+use crate::Corner;
+use crate::Edge;
+use crate::Face;
+use crate::Facet;
+use crate::Move;
+use crate::RotationDirection;
+use crate::RubiksCube;
+use crate::Slice;
+use crate::ValidationError;
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct CubieCube {
+    pub corner_permutation: [Corner; 8],
+    pub corner_orientation: [u8; 8],
+    pub edge_permutation: [Edge; 12],
+    pub edge_orientation: [u8; 12],
+}
+
+// Faces in the order up, right, front, down, left, back of the home
+// orientation. Facelets below are (face, position) pairs in that order, with
+// positions numbered like `Face::facets`.
+pub(crate) const FACES: [ColorFacet; 6] = [
+    ColorFacet::White,
+    ColorFacet::Blue,
+    ColorFacet::Red,
+    ColorFacet::Yellow,
+    ColorFacet::Green,
+    ColorFacet::Orange,
+];
+
+const U: usize = 0;
+const R: usize = 1;
+const F: usize = 2;
+const D: usize = 3;
+const L: usize = 4;
+const B: usize = 5;
+
+// The first facelet of every corner is on the up or down face, the others
+// follow clockwise.
+pub(crate) const CORNER_FACELETS: [[(usize, usize); 3]; 8] = [
+    [(U, 8), (R, 0), (F, 2)],
+    [(U, 6), (F, 0), (L, 2)],
+    [(U, 0), (L, 0), (B, 2)],
+    [(U, 2), (B, 0), (R, 2)],
+    [(D, 2), (F, 8), (R, 6)],
+    [(D, 0), (L, 8), (F, 6)],
+    [(D, 6), (B, 8), (L, 6)],
+    [(D, 8), (R, 8), (B, 6)],
+];
+
+pub(crate) const EDGE_FACELETS: [[(usize, usize); 2]; 12] = [
+    [(U, 5), (R, 1)],
+    [(U, 7), (F, 1)],
+    [(U, 3), (L, 1)],
+    [(U, 1), (B, 1)],
+    [(D, 5), (R, 7)],
+    [(D, 1), (F, 7)],
+    [(D, 3), (L, 7)],
+    [(D, 7), (B, 7)],
+    [(F, 5), (R, 3)],
+    [(F, 3), (L, 5)],
+    [(B, 5), (L, 3)],
+    [(B, 3), (R, 5)],
+];
+
+use Corner::*;
+use Edge::*;
+
+const SOLVED: CubieCube = CubieCube {
+    corner_permutation: [Urf, Ufl, Ulb, Ubr, Dfr, Dlf, Dbl, Drb],
+    corner_orientation: [0; 8],
+    edge_permutation: [Ur, Uf, Ul, Ub, Dr, Df, Dl, Db, Fr, Fl, Bl, Br],
+    edge_orientation: [0; 12],
+};
+
+// Clockwise quarter turns of the faces, in the same order as `FACES`.
+const FACE_MOVES: [CubieCube; 6] = [
+    CubieCube {
+        corner_permutation: [Ubr, Urf, Ufl, Ulb, Dfr, Dlf, Dbl, Drb],
+        corner_orientation: [0; 8],
+        edge_permutation: [Ub, Ur, Uf, Ul, Dr, Df, Dl, Db, Fr, Fl, Bl, Br],
+        edge_orientation: [0; 12],
+    },
+    CubieCube {
+        corner_permutation: [Dfr, Ufl, Ulb, Urf, Drb, Dlf, Dbl, Ubr],
+        corner_orientation: [2, 0, 0, 1, 1, 0, 0, 2],
+        edge_permutation: [Fr, Uf, Ul, Ub, Br, Df, Dl, Db, Dr, Fl, Bl, Ur],
+        edge_orientation: [0; 12],
+    },
+    CubieCube {
+        corner_permutation: [Ufl, Dlf, Ulb, Ubr, Urf, Dfr, Dbl, Drb],
+        corner_orientation: [1, 2, 0, 0, 2, 1, 0, 0],
+        edge_permutation: [Ur, Fl, Ul, Ub, Dr, Fr, Dl, Db, Uf, Df, Bl, Br],
+        edge_orientation: [0, 1, 0, 0, 0, 1, 0, 0, 1, 1, 0, 0],
+    },
+    CubieCube {
+        corner_permutation: [Urf, Ufl, Ulb, Ubr, Dlf, Dbl, Drb, Dfr],
+        corner_orientation: [0; 8],
+        edge_permutation: [Ur, Uf, Ul, Ub, Df, Dl, Db, Dr, Fr, Fl, Bl, Br],
+        edge_orientation: [0; 12],
+    },
+    CubieCube {
+        corner_permutation: [Urf, Ulb, Dbl, Ubr, Dfr, Ufl, Dlf, Drb],
+        corner_orientation: [0, 1, 2, 0, 0, 2, 1, 0],
+        edge_permutation: [Ur, Uf, Bl, Ub, Dr, Df, Fl, Db, Fr, Ul, Dl, Br],
+        edge_orientation: [0; 12],
+    },
+    CubieCube {
+        corner_permutation: [Urf, Ufl, Ubr, Drb, Dfr, Dlf, Ulb, Dbl],
+        corner_orientation: [0, 0, 1, 2, 0, 0, 2, 1],
+        edge_permutation: [Ur, Uf, Ul, Br, Dr, Df, Dl, Bl, Fr, Fl, Ub, Db],
+        edge_orientation: [0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 1, 1],
+    },
+];
+
+// Clockwise quarter turns of the middle, equator and standing slices seen
+// from a fixed point in space. Slices only move edges here, their centers are
+// accounted for by re-expressing the cube from its new centers.
+const SLICE_MOVES: [CubieCube; 3] = [
+    CubieCube {
+        corner_permutation: [Urf, Ufl, Ulb, Ubr, Dfr, Dlf, Dbl, Drb],
+        corner_orientation: [0; 8],
+        edge_permutation: [Ur, Ub, Ul, Db, Dr, Uf, Dl, Df, Fr, Fl, Bl, Br],
+        edge_orientation: [0, 1, 0, 1, 0, 1, 0, 1, 0, 0, 0, 0],
+    },
+    CubieCube {
+        corner_permutation: [Urf, Ufl, Ulb, Ubr, Dfr, Dlf, Dbl, Drb],
+        corner_orientation: [0; 8],
+        edge_permutation: [Ur, Uf, Ul, Ub, Dr, Df, Dl, Db, Fl, Bl, Br, Fr],
+        edge_orientation: [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1],
+    },
+    CubieCube {
+        corner_permutation: [Urf, Ufl, Ulb, Ubr, Dfr, Dlf, Dbl, Drb],
+        corner_orientation: [0; 8],
+        edge_permutation: [Ul, Uf, Dl, Ub, Ur, Df, Dr, Db, Fr, Fl, Bl, Br],
+        edge_orientation: [1, 0, 1, 0, 1, 0, 1, 0, 0, 0, 0, 0],
+    },
+];
+
+impl Default for CubieCube {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CubieCube {
+    pub fn new() -> CubieCube {
+        SOLVED
+    }
+
+    // Applies `other` after `self`.
+    pub fn multiply(self, other: CubieCube) -> CubieCube {
+        let mut product = self;
+        for i in 0..8 {
+            let from = other.corner_permutation[i] as usize;
+            product.corner_permutation[i] = self.corner_permutation[from];
+            product.corner_orientation[i] =
+                (self.corner_orientation[from] + other.corner_orientation[i]) % 3;
+        }
+        for i in 0..12 {
+            let from = other.edge_permutation[i] as usize;
+            product.edge_permutation[i] = self.edge_permutation[from];
+            product.edge_orientation[i] =
+                (self.edge_orientation[from] + other.edge_orientation[i]) % 2;
+        }
+        product
+    }
+
+    pub fn apply(self, algorithm: &Algorithm) -> CubieCube {
+        algorithm
+            .moves
+            .iter()
+            .fold(self, |cube, cube_move| cube.apply_move(*cube_move))
+    }
+
+    pub fn apply_move(self, cube_move: Move) -> CubieCube {
+        match cube_move {
+            Move::Face(face, direction) => self.rotate(face, direction),
+            Move::Slice(slice, direction) => self.rotate_slice(slice, direction),
+            Move::Rotation(axis, direction) => self.rotate_cube(axis, direction),
+            Move::Wide(face, direction) => self.rotate_wide(face, direction),
+        }
+    }
+
+    pub fn rotate(self, face: ColorFacet, direction: RotationDirection) -> CubieCube {
+        let face_move = FACE_MOVES[FACES.iter().position(|f| *f == face).unwrap()];
+        self.multiply_turns(face_move, direction)
+    }
+
+    // Slices and whole cube rotations move the centers, so the cube is
+    // described again relative to the centers it ends up with.
+    pub fn rotate_slice(self, slice: Slice, direction: RotationDirection) -> CubieCube {
+        let opposite = direction.opposite();
+        match slice {
+            Slice::Middle => self
+                .rotate(ColorFacet::Green, opposite)
+                .rotate(ColorFacet::Blue, direction)
+                .rotate_cube(Axis::X, opposite),
+            Slice::Equator => self
+                .rotate(ColorFacet::White, direction)
+                .rotate(ColorFacet::Yellow, opposite)
+                .rotate_cube(Axis::Y, opposite),
+            Slice::Standing => self
+                .rotate(ColorFacet::Red, opposite)
+                .rotate(ColorFacet::Orange, direction)
+                .rotate_cube(Axis::Z, direction),
+        }
+    }
+
+    // Turns a slice as seen from a fixed point in space, leaving the cube
+    // described from the centers it had. Methods keeping track of the
+    // centers themselves turn slices this way.
+    pub(crate) fn rotate_slice_in_place(
+        self,
+        slice: Slice,
+        direction: RotationDirection,
+    ) -> CubieCube {
+        let index = match slice {
+            Slice::Middle => 0,
+            Slice::Equator => 1,
+            Slice::Standing => 2,
+        };
+        self.multiply_turns(SLICE_MOVES[index], direction)
+    }
+
+    pub fn rotate_wide(self, face: ColorFacet, direction: RotationDirection) -> CubieCube {
+        let opposite = direction.opposite();
+        match face {
+            ColorFacet::Blue => self
+                .rotate(ColorFacet::Green, direction)
+                .rotate_cube(Axis::X, direction),
+            ColorFacet::Green => self
+                .rotate(ColorFacet::Blue, direction)
+                .rotate_cube(Axis::X, opposite),
+            ColorFacet::White => self
+                .rotate(ColorFacet::Yellow, direction)
+                .rotate_cube(Axis::Y, direction),
+            ColorFacet::Yellow => self
+                .rotate(ColorFacet::White, direction)
+                .rotate_cube(Axis::Y, opposite),
+            ColorFacet::Red => self
+                .rotate(ColorFacet::Orange, direction)
+                .rotate_cube(Axis::Z, direction),
+            ColorFacet::Orange => self
+                .rotate(ColorFacet::Red, direction)
+                .rotate_cube(Axis::Z, opposite),
+        }
+    }
+
+    pub fn rotate_cube(self, axis: Axis, direction: RotationDirection) -> CubieCube {
+        let rotation = CubieCube::rotation(axis);
+        let inverse_rotation = rotation.multiply(rotation).multiply(rotation);
+        (0..direction.quarter_turns()).fold(self, |cube, _| {
+            inverse_rotation.multiply(cube).multiply(rotation)
+        })
+    }
+
+    // Where the pieces go when the whole cube turns like the blue (x), white
+    // (y) or red (z) face.
+    fn rotation(axis: Axis) -> CubieCube {
+        let (face, slice, opposite_face, slice_direction) = match axis {
+            Axis::X => (R, 0, L, RotationDirection::Anticlockwise),
+            Axis::Y => (U, 1, D, RotationDirection::Anticlockwise),
+            Axis::Z => (F, 2, B, RotationDirection::Clockwise),
+        };
+        FACE_MOVES[face]
+            .multiply_turns(SLICE_MOVES[slice], slice_direction)
+            .multiply_turns(FACE_MOVES[opposite_face], RotationDirection::Anticlockwise)
+    }
+
+    fn multiply_turns(self, quarter_turn: CubieCube, direction: RotationDirection) -> CubieCube {
+        (0..direction.quarter_turns()).fold(self, |cube, _| cube.multiply(quarter_turn))
+    }
+
+    // Reads the cubies relative to the centers, so a cube turned with x, y
+    // or z converts to the same cubies as before the rotation.
+    pub fn from_rubiks_cube(cube: &RubiksCube) -> Result<CubieCube, ValidationError> {
+        let centers: Vec<ColorFacet> = FACES
+            .iter()
+            .map(|face| cube.face(*face).center.color)
+            .collect();
+        if (0..6).any(|i| centers[i + 1..].contains(&centers[i])) {
+            return Err(ValidationError::InvalidCenters);
+        }
+        let faces: Vec<[Facet; 9]> = FACES.iter().map(|face| cube.face(*face).facets()).collect();
+        let face_of = |(face, position): (usize, usize)| {
+            let color = faces[face][position].color;
+            centers.iter().position(|center| *center == color).unwrap()
+        };
+
+        let mut cubie_cube = CubieCube::new();
+        for (position, facelets) in CORNER_FACELETS.iter().enumerate() {
+            let unknown = ValidationError::UnknownCorner(Corner::from_index(position));
+            let orientation = (0..3)
+                .find(|i| [U, D].contains(&face_of(facelets[*i])))
+                .ok_or(unknown)?;
+            let first = face_of(facelets[(orientation + 1) % 3]);
+            let second = face_of(facelets[(orientation + 2) % 3]);
+            let corner = CORNER_FACELETS
+                .iter()
+                .position(|home| {
+                    home[0].0 == face_of(facelets[orientation])
+                        && home[1].0 == first
+                        && home[2].0 == second
+                })
+                .ok_or(unknown)?;
+            cubie_cube.corner_permutation[position] = Corner::from_index(corner);
+            cubie_cube.corner_orientation[position] = orientation as u8;
+        }
+
+        for (position, facelets) in EDGE_FACELETS.iter().enumerate() {
+            let colors = (face_of(facelets[0]), face_of(facelets[1]));
+            let (edge, orientation) = EDGE_FACELETS
+                .iter()
+                .enumerate()
+                .find_map(|(edge, home)| {
+                    if (home[0].0, home[1].0) == colors {
+                        Some((edge, 0))
+                    } else if (home[1].0, home[0].0) == colors {
+                        Some((edge, 1))
+                    } else {
+                        None
+                    }
+                })
+                .ok_or_else(|| ValidationError::UnknownEdge(Edge::from_index(position)))?;
+            cubie_cube.edge_permutation[position] = Edge::from_index(edge);
+            cubie_cube.edge_orientation[position] = orientation;
+        }
+        cubie_cube.check_cubies()?;
+        Ok(cubie_cube)
+    }
+
+    // A cube built from stickers is only solvable if it passes these
+    // checks, which are the invariants every sequence of moves keeps.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        self.check_cubies()?;
+        let twist: u32 = self.corner_orientation.iter().map(|o| *o as u32).sum();
+        if twist % 3 != 0 {
+            return Err(ValidationError::TwistedCorner);
+        }
+        let flip: u32 = self.edge_orientation.iter().map(|o| *o as u32).sum();
+        if flip % 2 != 0 {
+            return Err(ValidationError::FlippedEdge);
+        }
+        if self.corner_parity() != self.edge_parity() {
+            return Err(ValidationError::PermutationParity);
+        }
+        Ok(())
+    }
+
+    fn check_cubies(&self) -> Result<(), ValidationError> {
+        for corner in Corner::iterator() {
+            match self
+                .corner_permutation
+                .iter()
+                .filter(|c| *c == corner)
+                .count()
+            {
+                0 => return Err(ValidationError::MissingCorner(*corner)),
+                1 => {}
+                _ => return Err(ValidationError::DuplicateCorner(*corner)),
+            }
+        }
+        for edge in Edge::iterator() {
+            match self.edge_permutation.iter().filter(|e| *e == edge).count() {
+                0 => return Err(ValidationError::MissingEdge(*edge)),
+                1 => {}
+                _ => return Err(ValidationError::DuplicateEdge(*edge)),
+            }
+        }
+        Ok(())
+    }
+
+    pub fn corner_parity(&self) -> u8 {
+        let permutation: Vec<usize> = self
+            .corner_permutation
+            .iter()
+            .map(|c| *c as usize)
+            .collect();
+        permutation_parity(&permutation)
+    }
+
+    pub fn edge_parity(&self) -> u8 {
+        let permutation: Vec<usize> = self.edge_permutation.iter().map(|e| *e as usize).collect();
+        permutation_parity(&permutation)
+    }
+
+    // Builds the cube in its home orientation, every facet keeping the index
+    // it has on a solved cube.
+    pub fn to_rubiks_cube(&self) -> RubiksCube {
+        let mut faces: Vec<[Facet; 9]> =
+            FACES.iter().map(|face| Face::new(*face).facets()).collect();
+        let home_facet =
+            |(face, position): (usize, usize)| Facet::new(FACES[face], position as u8 + 1);
+        for (position, facelets) in CORNER_FACELETS.iter().enumerate() {
+            let corner = self.corner_permutation[position] as usize;
+            let orientation = self.corner_orientation[position] as usize;
+            for i in 0..3 {
+                let (face, facet) = facelets[(i + orientation) % 3];
+                faces[face][facet] = home_facet(CORNER_FACELETS[corner][i]);
+            }
+        }
+        for (position, facelets) in EDGE_FACELETS.iter().enumerate() {
+            let edge = self.edge_permutation[position] as usize;
+            let orientation = self.edge_orientation[position] as usize;
+            for i in 0..2 {
+                let (face, facet) = facelets[(i + orientation) % 2];
+                faces[face][facet] = home_facet(EDGE_FACELETS[edge][i]);
+            }
+        }
+        FACES
+            .iter()
+            .zip(faces.iter())
+            .fold(RubiksCube::new(), |cube, (face, facets)| {
+                cube.with_face(*face, Face::from_facets(*facets))
+            })
+    }
+
+    // Builds the cube held as `orientation` turns it, with the cubies read
+    // relative to its centers as `from_rubiks_cube` does.
+    pub fn to_rubiks_cube_oriented(&self, orientation: &Algorithm) -> RubiksCube {
+        let home = orientation
+            .moves
+            .iter()
+            .rev()
+            .fold(*self, |cube, cube_move| {
+                cube.apply_move(cube_move.inverse())
+            });
+        home.to_rubiks_cube().apply(orientation)
+    }
+
+    // Coordinates numbering the states of each part of the cube, as used by
+    // the two-phase solver. The solved cube is 0 in every coordinate.
+    pub fn twist(&self) -> usize {
+        self.corner_orientation[..7]
+            .iter()
+            .fold(0, |twist, o| twist * 3 + *o as usize)
+    }
+
+    pub fn with_twist(self, twist: usize) -> CubieCube {
+        let mut cube = self;
+        let mut rest = twist;
+        for i in (0..7).rev() {
+            cube.corner_orientation[i] = (rest % 3) as u8;
+            rest /= 3;
+        }
+        let sum: u8 = cube.corner_orientation[..7].iter().sum();
+        cube.corner_orientation[7] = (3 - sum % 3) % 3;
+        cube
+    }
+
+    pub fn flip(&self) -> usize {
+        self.edge_orientation[..11]
+            .iter()
+            .fold(0, |flip, o| flip * 2 + *o as usize)
+    }
+
+    pub fn with_flip(self, flip: usize) -> CubieCube {
+        let mut cube = self;
+        let mut rest = flip;
+        for i in (0..11).rev() {
+            cube.edge_orientation[i] = (rest % 2) as u8;
+            rest /= 2;
+        }
+        let sum: u8 = cube.edge_orientation[..11].iter().sum();
+        cube.edge_orientation[11] = sum % 2;
+        cube
+    }
+
+    // Where the four middle layer edges are, times 24, plus their order. It
+    // is below 24 exactly when they are all in the middle layer.
+    pub fn slice_sorted(&self) -> usize {
+        let mut combination = 0;
+        let mut slice_edges = Vec::new();
+        for j in (0..12).rev() {
+            let edge = self.edge_permutation[j] as usize;
+            if edge >= Fr as usize {
+                combination += binomial(11 - j, slice_edges.len() + 1);
+                slice_edges.insert(0, edge - Fr as usize);
+            }
+        }
+        24 * combination + permutation_index(&slice_edges)
+    }
+
+    pub fn with_slice_sorted(self, slice_sorted: usize) -> CubieCube {
+        let mut cube = self;
+        let slice_edges = permutation_from_index(slice_sorted % 24, 4);
+        let mut combination = slice_sorted / 24;
+        let mut slice_edge = 0;
+        let mut other_edge = 0;
+        for j in 0..12 {
+            let left = 4 - slice_edge;
+            if left > 0 && combination >= binomial(11 - j, left) {
+                combination -= binomial(11 - j, left);
+                cube.edge_permutation[j] = Edge::from_index(slice_edges[slice_edge] + Fr as usize);
+                slice_edge += 1;
+            } else {
+                cube.edge_permutation[j] = Edge::from_index(other_edge);
+                other_edge += 1;
+            }
+        }
+        cube
+    }
+
+    pub fn corners(&self) -> usize {
+        let permutation: Vec<usize> = self
+            .corner_permutation
+            .iter()
+            .map(|c| *c as usize)
+            .collect();
+        permutation_index(&permutation)
+    }
+
+    pub fn with_corners(self, corners: usize) -> CubieCube {
+        let mut cube = self;
+        for (i, corner) in permutation_from_index(corners, 8).iter().enumerate() {
+            cube.corner_permutation[i] = Corner::from_index(*corner);
+        }
+        cube
+    }
+
+    // The order of the eight up and down layer edges, for cubes whose middle
+    // layer edges are all in the middle layer.
+    pub fn ud_edges(&self) -> usize {
+        let permutation: Vec<usize> = self.edge_permutation[..8]
+            .iter()
+            .map(|e| *e as usize)
+            .collect();
+        permutation_index(&permutation)
+    }
+
+    pub fn with_ud_edges(self, ud_edges: usize) -> CubieCube {
+        let mut cube = self;
+        for (i, edge) in permutation_from_index(ud_edges, 8).iter().enumerate() {
+            cube.edge_permutation[i] = Edge::from_index(*edge);
+        }
+        cube
+    }
+
+    // The order of all twelve edges.
+    pub fn edges(&self) -> usize {
+        let permutation: Vec<usize> = self.edge_permutation.iter().map(|e| *e as usize).collect();
+        permutation_index(&permutation)
+    }
+
+    pub fn with_edges(self, edges: usize) -> CubieCube {
+        let mut cube = self;
+        for (i, edge) in permutation_from_index(edges, 12).iter().enumerate() {
+            cube.edge_permutation[i] = Edge::from_index(*edge);
+        }
+        cube
+    }
+}
+
+fn binomial(n: usize, k: usize) -> usize {
+    if k > n {
+        return 0;
+    }
+    (0..k).fold(1, |result, i| result * (n - i) / (i + 1))
+}
+
+// Numbers the permutations of 0..n by how many times each prefix has to be
+// rotated left to put its last element in place.
+fn permutation_index(permutation: &[usize]) -> usize {
+    let mut permutation = permutation.to_vec();
+    let mut index = 0;
+    for j in (1..permutation.len()).rev() {
+        let mut k = 0;
+        while permutation[j] != j {
+            permutation[..=j].rotate_left(1);
+            k += 1;
+        }
+        index = (j + 1) * index + k;
+    }
+    index
+}
+
+fn permutation_from_index(index: usize, n: usize) -> Vec<usize> {
+    let mut permutation: Vec<usize> = (0..n).collect();
+    let mut rest = index;
+    for j in 1..n {
+        permutation[..=j].rotate_right(rest % (j + 1));
+        rest /= j + 1;
+    }
+    permutation
+}
+
+fn permutation_parity(permutation: &[usize]) -> u8 {
+    let mut inversions = 0;
+    for i in 0..permutation.len() {
+        for j in i + 1..permutation.len() {
+            if permutation[i] > permutation[j] {
+                inversions += 1;
+            }
+        }
+    }
+    inversions % 2
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scramble() -> Algorithm {
+        "R U2 F' L D' B2 R' U F2 L' D B'".parse().unwrap()
+    }
+
+    #[test]
+    fn test_conversion_should_round_trip() {
+        let rubiks_cube = RubiksCube::new().apply(&scramble());
+        let cubie_cube = CubieCube::from_rubiks_cube(&rubiks_cube).unwrap();
+        assert_eq!(cubie_cube.to_rubiks_cube(), rubiks_cube);
+        assert_eq!(
+            CubieCube::from_rubiks_cube(&cubie_cube.to_rubiks_cube()),
+            Ok(cubie_cube)
+        );
+    }
+
+    #[test]
+    fn test_solved_cube_should_convert_to_identity() {
+        assert_eq!(
+            CubieCube::from_rubiks_cube(&RubiksCube::new()),
+            Ok(CubieCube::new())
+        );
+        assert_eq!(CubieCube::new().to_rubiks_cube(), RubiksCube::new());
+    }
+
+    #[test]
+    fn test_every_move_should_match_facet_model() {
+        let rubiks_cube = RubiksCube::new().apply(&scramble());
+        let cubie_cube = CubieCube::from_rubiks_cube(&rubiks_cube).unwrap();
+        let letters = "UDLRFBMESxyzudlrfb";
+        for letter in letters.chars() {
+            for direction in RotationDirection::iterator() {
+                let cube_move = Move::from_letter(letter, *direction).unwrap();
+                assert_eq!(
+                    CubieCube::from_rubiks_cube(&rubiks_cube.apply_move(cube_move)),
+                    Ok(cubie_cube.apply_move(cube_move)),
+                    "{}",
+                    cube_move
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_rotated_cube_should_convert_relative_to_centers() {
+        let rubiks_cube = RubiksCube::new().apply(&"x y2 z'".parse().unwrap());
+        assert_eq!(
+            CubieCube::from_rubiks_cube(&rubiks_cube),
+            Ok(CubieCube::new())
+        );
+    }
+
+    #[test]
+    fn test_conversion_should_reject_impossible_stickers() {
+        let mut rubiks_cube = RubiksCube::new();
+        rubiks_cube.white.center = rubiks_cube.red.center;
+        assert_eq!(
+            CubieCube::from_rubiks_cube(&rubiks_cube),
+            Err(ValidationError::InvalidCenters)
+        );
+
+        let mut rubiks_cube = RubiksCube::new();
+        rubiks_cube.white.right_bottom.color = ColorFacet::Yellow;
+        assert_eq!(
+            CubieCube::from_rubiks_cube(&rubiks_cube),
+            Err(ValidationError::UnknownCorner(Corner::Urf))
+        );
+
+        let mut rubiks_cube = RubiksCube::new();
+        rubiks_cube.orange.top.color = ColorFacet::Red;
+        assert_eq!(
+            CubieCube::from_rubiks_cube(&rubiks_cube),
+            Err(ValidationError::DuplicateEdge(Edge::Uf))
+        );
+
+        let mut rubiks_cube = RubiksCube::new();
+        rubiks_cube.red.top.color = ColorFacet::Red;
+        rubiks_cube.white.bottom.color = ColorFacet::Red;
+        assert_eq!(
+            CubieCube::from_rubiks_cube(&rubiks_cube),
+            Err(ValidationError::UnknownEdge(Edge::Uf))
+        );
+    }
+
+    #[test]
+    fn test_validate_should_check_orientations_and_parity() {
+        assert_eq!(CubieCube::new().apply(&scramble()).validate(), Ok(()));
+
+        let mut cubie_cube = CubieCube::new();
+        cubie_cube.corner_orientation[0] = 1;
+        assert_eq!(cubie_cube.validate(), Err(ValidationError::TwistedCorner));
+
+        let mut cubie_cube = CubieCube::new();
+        cubie_cube.edge_orientation[5] = 1;
+        assert_eq!(cubie_cube.validate(), Err(ValidationError::FlippedEdge));
+
+        let mut cubie_cube = CubieCube::new();
+        cubie_cube.edge_permutation.swap(0, 1);
+        assert_eq!(
+            cubie_cube.validate(),
+            Err(ValidationError::PermutationParity)
+        );
+
+        let mut cubie_cube = CubieCube::new();
+        cubie_cube.corner_permutation[3] = Corner::Urf;
+        assert_eq!(
+            cubie_cube.validate(),
+            Err(ValidationError::DuplicateCorner(Corner::Urf))
+        );
+    }
+
+    #[test]
+    fn test_coordinates_should_round_trip() {
+        let cubie_cube = CubieCube::new().apply(&scramble());
+        let solved = CubieCube::new();
+        assert_eq!(solved.twist(), 0);
+        assert_eq!(solved.flip(), 0);
+        assert_eq!(solved.slice_sorted(), 0);
+        assert_eq!(solved.corners(), 0);
+        assert_eq!(solved.ud_edges(), 0);
+        assert_eq!(solved.edges(), 0);
+        assert_eq!(
+            solved.with_twist(cubie_cube.twist()).corner_orientation,
+            cubie_cube.corner_orientation
+        );
+        assert_eq!(
+            solved.with_flip(cubie_cube.flip()).edge_orientation,
+            cubie_cube.edge_orientation
+        );
+        assert_eq!(
+            solved.with_corners(cubie_cube.corners()).corner_permutation,
+            cubie_cube.corner_permutation
+        );
+        assert_eq!(
+            solved.with_edges(cubie_cube.edges()).edge_permutation,
+            cubie_cube.edge_permutation
+        );
+        for slice_sorted in 0..11880 {
+            assert_eq!(
+                solved.with_slice_sorted(slice_sorted).slice_sorted(),
+                slice_sorted
+            );
+        }
+        for ud_edges in (0..40320).step_by(97) {
+            assert_eq!(solved.with_ud_edges(ud_edges).ud_edges(), ud_edges);
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use std::slice::Iter;
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Edge {
+    Ur,
+    Uf,
+    Ul,
+    Ub,
+    Dr,
+    Df,
+    Dl,
+    Db,
+    Fr,
+    Fl,
+    Bl,
+    Br,
+}
+
+static EDGE: [Edge; 12] = [
+    Edge::Ur,
+    Edge::Uf,
+    Edge::Ul,
+    Edge::Ub,
+    Edge::Dr,
+    Edge::Df,
+    Edge::Dl,
+    Edge::Db,
+    Edge::Fr,
+    Edge::Fl,
+    Edge::Bl,
+    Edge::Br,
+];
+
+impl Edge {
+    pub fn iterator() -> Iter<'static, Edge> {
+        EDGE.iter()
+    }
+
+    pub fn from_index(index: usize) -> Edge {
+        EDGE[index]
+    }
+}
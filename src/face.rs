@@ -48,10 +48,39 @@ impl Face {
         }
     }
 
+    pub fn facets(&self) -> [Facet; 9] {
+        [
+            self.left_top,
+            self.top,
+            self.right_top,
+            self.left,
+            self.center,
+            self.right,
+            self.left_bottom,
+            self.bottom,
+            self.right_bottom,
+        ]
+    }
+
+    pub fn from_facets(facets: [Facet; 9]) -> Face {
+        Face {
+            left_top: facets[0],
+            top: facets[1],
+            right_top: facets[2],
+            left: facets[3],
+            center: facets[4],
+            right: facets[5],
+            left_bottom: facets[6],
+            bottom: facets[7],
+            right_bottom: facets[8],
+        }
+    }
+
     pub fn rotate(self, direction: RotationDirection) -> Face {
         match direction {
             RotationDirection::Clockwise => Face::rotate_clockwise(self),
             RotationDirection::Anticlockwise => Face::rotate_anticlockwise(self),
+            RotationDirection::HalfTurn => Face::rotate_half_turn(self),
         }
     }
 
@@ -82,4 +111,18 @@ impl Face {
             ..self
         }
     }
+
+    pub fn rotate_half_turn(self) -> Face {
+        Face {
+            left_top: self.right_bottom,
+            top: self.bottom,
+            right_top: self.left_bottom,
+            left: self.right,
+            right: self.left,
+            left_bottom: self.right_top,
+            bottom: self.top,
+            right_bottom: self.left_top,
+            ..self
+        }
+    }
 }
@@ -0,0 +1,35 @@
+use crate::ColorFacet;
+use crate::Facet;
+
+// The facets of one face of an NxN cube, by row then column as the face is
+// laid out in the net, the same way `Face` lays out a 3x3 face.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct FaceGrid<const N: usize> {
+    pub facets: [[Facet; N]; N],
+}
+
+impl<const N: usize> FaceGrid<N> {
+    // Facet indexes are bytes, so faces are at most 15x15: building a larger
+    // one fails to compile rather than giving two facets the same index.
+    const INDEXES_FIT: () = assert!(N * N <= u8::MAX as usize, "faces are at most 15x15");
+
+    // Facets are indexed from 1, row by row.
+    pub fn new(color: ColorFacet) -> FaceGrid<N> {
+        let () = Self::INDEXES_FIT;
+        let mut facets = [[Facet::new(color, 0); N]; N];
+        for (row, line) in facets.iter_mut().enumerate() {
+            for (column, facet) in line.iter_mut().enumerate() {
+                facet.index = (row * N + column + 1) as u8;
+            }
+        }
+        FaceGrid { facets }
+    }
+
+    pub fn is_one_color(&self) -> bool {
+        let color = self.facets[0][0].color;
+        self.facets
+            .iter()
+            .flatten()
+            .all(|facet| facet.color == color)
+    }
+}
@@ -0,0 +1,34 @@
+use crate::ValidationError;
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum FaceletError {
+    InvalidScheme,
+    WrongLength(usize),
+    UnknownLetter(usize, char),
+    Unsolvable(ValidationError),
+}
+
+impl std::fmt::Display for FaceletError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            FaceletError::InvalidScheme => {
+                write!(f, "color scheme does not have six distinct colors")
+            }
+            FaceletError::WrongLength(length) => {
+                write!(f, "expected 54 facelets, found {}", length)
+            }
+            FaceletError::UnknownLetter(position, letter) => {
+                write!(f, "unknown facelet `{}` at position {}", letter, position)
+            }
+            FaceletError::Unsolvable(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for FaceletError {}
+
+impl From<ValidationError> for FaceletError {
+    fn from(error: ValidationError) -> Self {
+        FaceletError::Unsolvable(error)
+    }
+}
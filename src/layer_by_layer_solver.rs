@@ -0,0 +1,276 @@
+use crate::cubie_cube::EDGE_FACELETS;
+use crate::solving_cube::{
+    corner_position, corner_solved, edge_position, edge_solved, solve_cross, SolvingCube, SIDES,
+    SLOT_SIDES, UP_TURNS,
+};
+use crate::CubieCube;
+use crate::RubiksCube;
+use crate::Solution;
+use crate::SolveError;
+
+// Position of the up layer edge above each side, in the order of `SIDES`.
+const EDGES_ABOVE: [usize; 4] = [1, 0, 3, 2];
+
+const TRIGGER: &str = "R U R' U'";
+const RIGHT_INSERT: &str = "U R U' R' U' F' U F";
+const LEFT_INSERT: &str = "U' L' U L U F U' F'";
+const EDGE_FLIP: &str = "F R U R' U' F'";
+const EDGE_FLIP_FROM_L: &str = "F U R U' R' F'";
+const SUNE: &str = "R U R' U R U2 R'";
+const CORNER_CYCLE: &str = "U R U' L' U R' U' L";
+const CORNER_TWIST: &str = "R' D' R D R' D' R D";
+
+#[derive(PartialEq, Debug, Clone, Copy, Default)]
+pub struct LayerByLayerSolver {}
+
+impl LayerByLayerSolver {
+    pub fn new() -> LayerByLayerSolver {
+        LayerByLayerSolver {}
+    }
+
+    // Solves the down layer, then the middle layer, then the up layer, with
+    // a stage for each step a beginner learns.
+    pub fn solve(&self, cube: &RubiksCube) -> Result<Solution, SolveError> {
+        let mut solving = SolvingCube::new(cube)?;
+        solve_cross(&mut solving);
+        solve_first_layer_corners(&mut solving);
+        solve_second_layer(&mut solving);
+        solve_last_layer_cross(&mut solving);
+        solve_last_layer_edges(&mut solving);
+        solve_corner_positions(&mut solving);
+        solve_corner_orientations(&mut solving);
+        Ok(solving.into_solution())
+    }
+}
+
+fn solve_first_layer_corners(solving: &mut SolvingCube) {
+    let mut cases = Vec::new();
+    for corner in 4..8 {
+        let name = solving.corner_name(corner);
+        if corner_solved(&solving.cube, corner) {
+            cases.push(format!("{} already placed", name));
+            continue;
+        }
+        let mut case = "in the up layer";
+        let position = corner_position(&solving.cube, corner);
+        if position >= 4 {
+            solving.apply_from(SLOT_SIDES[position - 4], TRIGGER);
+            case = "taken out of the wrong slot";
+        }
+        let turns = (0..4)
+            .find(|t| corner_position(&solving.after(0, UP_TURNS[*t]), corner) == corner - 4)
+            .unwrap();
+        solving.apply(UP_TURNS[turns]);
+        let mut triggers = 0;
+        while !corner_solved(&solving.cube, corner) {
+            solving.apply_from(SLOT_SIDES[corner - 4], TRIGGER);
+            triggers += 1;
+        }
+        cases.push(format!("{} {}, {} triggers", name, case, triggers));
+    }
+    solving.finish_stage("first layer corners", cases.join("; "));
+}
+
+fn solve_second_layer(solving: &mut SolvingCube) {
+    let mut cases = Vec::new();
+    for (edge, facelets) in EDGE_FACELETS.iter().enumerate().skip(8) {
+        let name = solving.edge_name(edge);
+        if edge_solved(&solving.cube, edge) {
+            cases.push(format!("{} already placed", name));
+            continue;
+        }
+        let mut case = "";
+        let position = edge_position(&solving.cube, edge);
+        if position >= 8 {
+            solving.apply_from(SLOT_SIDES[position - 8], RIGHT_INSERT);
+            case = "taken out of the wrong slot, ";
+        }
+        // The sticker on the side is matched with its center, the one on
+        // top tells which way the edge goes down.
+        let orientation = solving.cube.edge_orientation[edge_position(&solving.cube, edge)];
+        let front = facelets[1 ^ orientation as usize].0;
+        let top = facelets[orientation as usize].0;
+        let side = SIDES.iter().position(|s| *s == front).unwrap();
+        let turns = (0..4)
+            .find(|t| edge_position(&solving.after(0, UP_TURNS[*t]), edge) == EDGES_ABOVE[side])
+            .unwrap();
+        solving.apply(UP_TURNS[turns]);
+        if top == SIDES[(side + 1) % 4] {
+            solving.apply_from(side, RIGHT_INSERT);
+            cases.push(format!("{} {}inserted to the right", name, case));
+        } else {
+            solving.apply_from(side, LEFT_INSERT);
+            cases.push(format!("{} {}inserted to the left", name, case));
+        }
+    }
+    solving.finish_stage("second layer", cases.join("; "));
+}
+
+fn solve_last_layer_cross(solving: &mut SolvingCube) {
+    let oriented = |cube: &CubieCube, position: usize| cube.edge_orientation[position] == 0;
+    let mut cases = Vec::new();
+    loop {
+        let count = (0..4).filter(|p| oriented(&solving.cube, *p)).count();
+        if count == 4 {
+            break;
+        }
+        if count == 0 {
+            solving.apply(EDGE_FLIP);
+            cases.push("dot");
+        } else if oriented(&solving.cube, 0) == oriented(&solving.cube, 2) {
+            let turns = if oriented(&solving.cube, 0) { 0 } else { 1 };
+            solving.apply(UP_TURNS[turns]);
+            solving.apply(EDGE_FLIP);
+            cases.push("line");
+        } else {
+            let turns = (0..4)
+                .find(|t| {
+                    let cube = solving.after(0, UP_TURNS[*t]);
+                    oriented(&cube, 2) && oriented(&cube, 3)
+                })
+                .unwrap();
+            solving.apply(UP_TURNS[turns]);
+            solving.apply(EDGE_FLIP_FROM_L);
+            cases.push("L shape");
+        }
+    }
+    let description = if cases.is_empty() {
+        "cross already made".to_string()
+    } else {
+        format!("{}, then cross", cases.join(", then "))
+    };
+    solving.finish_stage("last layer cross", description);
+}
+
+fn solve_last_layer_edges(solving: &mut SolvingCube) {
+    let solved = |cube: &CubieCube| (0..4).all(|e| edge_solved(cube, e));
+    let matching = (0..4)
+        .map(|t| {
+            let cube = solving.after(0, UP_TURNS[t]);
+            (0..4).filter(|e| edge_solved(&cube, *e)).count()
+        })
+        .max()
+        .unwrap();
+    let mut description = format!("{} edges matching their sides", matching);
+    loop {
+        if let Some(turns) = (0..4).find(|t| solved(&solving.after(0, UP_TURNS[*t]))) {
+            solving.apply(UP_TURNS[turns]);
+            break;
+        }
+        let setup = (0..16).find(|i| {
+            let algorithm = format!("{} {} {}", UP_TURNS[i / 4], SUNE, UP_TURNS[i % 4]);
+            solved(&solving.after(0, &algorithm))
+        });
+        match setup {
+            Some(i) => {
+                solving.apply(UP_TURNS[i / 4]);
+                solving.apply(SUNE);
+                solving.apply(UP_TURNS[i % 4]);
+            }
+            None => {
+                solving.apply(SUNE);
+                description.push_str(", opposite edges swapped first");
+            }
+        }
+    }
+    solving.finish_stage("last layer edges", description);
+}
+
+fn solve_corner_positions(solving: &mut SolvingCube) {
+    let placed =
+        |cube: &CubieCube, corner: usize| cube.corner_permutation[corner] as usize == corner;
+    let all_placed = |cube: &CubieCube| (0..4).all(|c| placed(cube, c));
+    let count = (0..4).filter(|c| placed(&solving.cube, *c)).count();
+    let mut description = format!("{} corners already in place", count);
+    while !all_placed(&solving.cube) {
+        let cycle = (0..8).find(|i| {
+            let algorithm = vec![CORNER_CYCLE; i / 4 + 1].join(" ");
+            all_placed(&solving.after(i % 4, &algorithm))
+        });
+        match cycle {
+            Some(i) => {
+                for _ in 0..=i / 4 {
+                    solving.apply_from(i % 4, CORNER_CYCLE);
+                }
+            }
+            None => {
+                solving.apply(CORNER_CYCLE);
+                description.push_str(", cycled once to place one");
+            }
+        }
+    }
+    solving.finish_stage("last layer corner positions", description);
+}
+
+fn solve_corner_orientations(solving: &mut SolvingCube) {
+    let twisted = (0..4)
+        .filter(|c| solving.cube.corner_orientation[*c] != 0)
+        .count();
+    while (0..4).any(|c| solving.cube.corner_orientation[c] != 0) {
+        let turns = (0..4)
+            .find(|t| solving.after(0, UP_TURNS[*t]).corner_orientation[0] != 0)
+            .unwrap();
+        solving.apply(UP_TURNS[turns]);
+        while solving.cube.corner_orientation[0] != 0 {
+            solving.apply(CORNER_TWIST);
+        }
+    }
+    let turns = (0..4)
+        .find(|t| solving.after(0, UP_TURNS[*t]) == CubieCube::new())
+        .unwrap();
+    solving.apply(UP_TURNS[turns]);
+    let description = format!("{} corners twisted in place", twisted);
+    solving.finish_stage("last layer corner orientation", description);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solve_should_solve_scrambles_in_named_stages() {
+        let solver = LayerByLayerSolver::new();
+        let scrambles = [
+            "R U R' U'",
+            "F2 D' L B2 R' U2 F' D2 L2 B R2 U' F L' D B' R U2 L F'",
+            "U R2 F B R B2 R U2 L B2 R U' D' R2 F R' L B2 U2 F2",
+            "M' E S2 r U2 x y' F' D",
+            "L2 D B' U R2 F' L' D2 B U' R' F2 D L B2 U2 R D' F L'",
+        ];
+        for scramble in scrambles.iter() {
+            let cube = RubiksCube::new().apply(&scramble.parse().unwrap());
+            let solution = solver.solve(&cube).unwrap();
+            assert!(cube.apply(&solution.algorithm()).is_solved());
+            let names: Vec<&str> = solution.stages.iter().map(|s| s.name.as_str()).collect();
+            assert_eq!(
+                names,
+                vec![
+                    "cross",
+                    "first layer corners",
+                    "second layer",
+                    "last layer cross",
+                    "last layer edges",
+                    "last layer corner positions",
+                    "last layer corner orientation",
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn test_solve_should_describe_the_cases() {
+        let solver = LayerByLayerSolver::new();
+        let solution = solver.solve(&RubiksCube::new()).unwrap();
+        assert!(solution.is_empty());
+        assert_eq!(
+            solution.stage("cross").unwrap().description,
+            "yellow cross on the down face, 4 of 4 edges were already in place"
+        );
+        let cube = RubiksCube::new().apply(&"F R U R' U' F'".parse().unwrap());
+        let solution = solver.solve(&cube).unwrap();
+        assert_eq!(
+            solution.stage("last layer cross").unwrap().description,
+            "L shape, then cross"
+        );
+    }
+}
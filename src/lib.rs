@@ -1,11 +1,89 @@
+mod algorithm;
+mod analysis;
+mod axis;
+mod cfop_solver;
 mod color_facet;
+mod color_scheme;
+mod compact_cube;
+mod corner;
+mod cube;
+mod cube_move;
+mod cubie_cube;
+mod edge;
 mod face;
+mod face_grid;
+mod facelet_error;
 mod facet;
+mod layer_by_layer_solver;
+mod move_count;
+mod optimal_solver;
+mod parse_error;
+mod pattern_databases;
+mod piece_cycle;
+mod pocket_cube;
+mod pocket_cube_solver;
+mod random;
+mod random_move_scrambler;
+mod random_state_scrambler;
+mod reduction_solver;
 mod rotation_direction;
+mod roux_solver;
 mod rubiks_cube;
+mod scramble;
+mod search_progress;
+mod simplification;
+mod slice;
+mod solution;
+mod solution_stage;
+mod solve_error;
+mod solving_cube;
+mod super_cube;
+mod super_cube_solver;
+mod thistlethwaite_solver;
+mod two_phase_solver;
+mod two_phase_tables;
+mod validation_error;
 
+pub use crate::algorithm::Algorithm;
+pub use crate::analysis::Analysis;
+pub use crate::axis::Axis;
+pub use crate::cfop_solver::CfopSolver;
 pub use crate::color_facet::ColorFacet;
+pub use crate::color_scheme::ColorScheme;
+pub use crate::compact_cube::CompactCube;
+pub use crate::corner::Corner;
+pub use crate::cube::Cube;
+pub use crate::cube_move::Move;
+pub use crate::cubie_cube::CubieCube;
+pub use crate::edge::Edge;
 pub use crate::face::Face;
+pub use crate::face_grid::FaceGrid;
+pub use crate::facelet_error::FaceletError;
 pub use crate::facet::Facet;
+pub use crate::layer_by_layer_solver::LayerByLayerSolver;
+pub use crate::move_count::MoveCount;
+pub use crate::optimal_solver::OptimalSolver;
+pub use crate::parse_error::ParseError;
+pub use crate::pattern_databases::PatternDatabases;
+pub use crate::piece_cycle::PieceCycle;
+pub use crate::pocket_cube::PocketCube;
+pub use crate::pocket_cube_solver::PocketCubeSolver;
+pub use crate::random_move_scrambler::RandomMoveScrambler;
+pub use crate::random_state_scrambler::RandomStateScrambler;
+pub use crate::reduction_solver::ReductionSolver;
 pub use crate::rotation_direction::RotationDirection;
+pub use crate::roux_solver::RouxSolver;
 pub use crate::rubiks_cube::RubiksCube;
+pub use crate::scramble::Scramble;
+pub use crate::search_progress::SearchProgress;
+pub use crate::simplification::Simplification;
+pub use crate::slice::Slice;
+pub use crate::solution::Solution;
+pub use crate::solution_stage::SolutionStage;
+pub use crate::solve_error::SolveError;
+pub use crate::super_cube::SuperCube;
+pub use crate::super_cube_solver::SuperCubeSolver;
+pub use crate::thistlethwaite_solver::ThistlethwaiteSolver;
+pub use crate::two_phase_solver::TwoPhaseSolver;
+pub use crate::two_phase_tables::TwoPhaseTables;
+pub use crate::validation_error::ValidationError;
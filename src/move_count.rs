@@ -0,0 +1,15 @@
+// Lengths of an algorithm in the usual metrics: half turn (HTM), quarter
+// turn (QTM) and slice turn (STM). Cube rotations are free in all three,
+// and a slice move counts as two outer turns in HTM and QTM.
+#[derive(PartialEq, Debug, Clone, Copy, Default)]
+pub struct MoveCount {
+    pub htm: usize,
+    pub qtm: usize,
+    pub stm: usize,
+}
+
+impl std::fmt::Display for MoveCount {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{} HTM, {} QTM, {} STM", self.htm, self.qtm, self.stm)
+    }
+}
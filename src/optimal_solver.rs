@@ -0,0 +1,187 @@
+use crate::Algorithm;
+use crate::CubieCube;
+use crate::Move;
+use crate::PatternDatabases;
+use crate::RubiksCube;
+use crate::SearchProgress;
+use crate::SolveError;
+use std::sync::Arc;
+
+#[derive(Debug, Clone)]
+pub struct OptimalSolver {
+    pub max_length: usize,
+    databases: Arc<PatternDatabases>,
+}
+
+impl OptimalSolver {
+    pub fn new(databases: Arc<PatternDatabases>) -> OptimalSolver {
+        OptimalSolver {
+            max_length: 20,
+            databases,
+        }
+    }
+
+    pub fn max_length(self, max_length: usize) -> OptimalSolver {
+        OptimalSolver { max_length, ..self }
+    }
+
+    pub fn solve(&self, cube: &RubiksCube) -> Result<Algorithm, SolveError> {
+        let mut solutions = self.search(cube, false, |_| {})?;
+        Ok(solutions.remove(0))
+    }
+
+    pub fn solve_all(&self, cube: &RubiksCube) -> Result<Vec<Algorithm>, SolveError> {
+        self.search(cube, true, |_| {})
+    }
+
+    // Iterative deepening A*: searches every depth in turn, cutting branches
+    // the pattern databases prove too short, and reports after each depth.
+    // Sequences that only differ by the order of turns of opposite faces are
+    // returned once.
+    pub fn search<F>(
+        &self,
+        cube: &RubiksCube,
+        all: bool,
+        mut progress: F,
+    ) -> Result<Vec<Algorithm>, SolveError>
+    where
+        F: FnMut(&SearchProgress),
+    {
+        cube.validate()?;
+        let cubie_cube = CubieCube::from_rubiks_cube(cube)?;
+        let mut search = Search {
+            databases: &self.databases,
+            moves: Vec::new(),
+            solutions: Vec::new(),
+            all,
+            nodes: 0,
+        };
+        let start = self.databases.lower_bound(&cubie_cube) as usize;
+        for depth in start..=self.max_length {
+            search.search(cubie_cube, depth);
+            progress(&SearchProgress {
+                depth,
+                nodes: search.nodes,
+                solutions: search.solutions.len(),
+            });
+            if !search.solutions.is_empty() {
+                return Ok(search.solutions);
+            }
+        }
+        Err(SolveError::NotFound)
+    }
+}
+
+struct Search<'a> {
+    databases: &'a PatternDatabases,
+    moves: Vec<usize>,
+    solutions: Vec<Algorithm>,
+    all: bool,
+    nodes: u64,
+}
+
+impl Search<'_> {
+    // Returns whether to stop searching.
+    fn search(&mut self, cube: CubieCube, togo: usize) -> bool {
+        self.nodes += 1;
+        if togo == 0 {
+            if cube != CubieCube::new() {
+                return false;
+            }
+            let moves = self.moves.iter().map(|m| Move::face_turn(*m)).collect();
+            self.solutions.push(Algorithm::new(moves));
+            return !self.all;
+        }
+        for m in 0..18 {
+            if let Some(last) = self.moves.last() {
+                let (face, last_face) = (m / 3, last / 3);
+                if face == last_face || face + 3 == last_face {
+                    continue;
+                }
+            }
+            let next = cube.apply_move(Move::face_turn(m));
+            if self.databases.lower_bound(&next) as usize >= togo {
+                continue;
+            }
+            self.moves.push(m);
+            let stop = self.search(next, togo - 1);
+            self.moves.pop();
+            if stop {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::OnceLock;
+
+    fn databases() -> Arc<PatternDatabases> {
+        static DATABASES: OnceLock<Arc<PatternDatabases>> = OnceLock::new();
+        DATABASES
+            .get_or_init(|| Arc::new(PatternDatabases::new(1 << 20)))
+            .clone()
+    }
+
+    #[test]
+    fn test_memory_budget_should_choose_piece_count() {
+        let databases = databases();
+        assert_eq!(databases.corner_pieces(), 4);
+        assert_eq!(databases.edge_pieces(), 4);
+        assert_eq!(databases.memory_usage(), 136080 + 2 * 190080);
+        assert_eq!(PatternDatabases::new(0).memory_usage(), 3);
+    }
+
+    #[test]
+    fn test_solve_should_find_optimal_solution() {
+        let solver = OptimalSolver::new(databases());
+        let cube = RubiksCube::new().apply(&"R U R' U' F2 D".parse().unwrap());
+        let solution = solver.solve(&cube).unwrap();
+        assert_eq!(solution.len(), 6);
+        assert!(cube.apply(&solution).is_solved());
+        assert_eq!(solver.solve(&RubiksCube::new()), Ok(Algorithm::default()));
+    }
+
+    #[test]
+    fn test_solve_all_should_return_every_optimal_solution() {
+        let solver = OptimalSolver::new(databases());
+        let cube = RubiksCube::new().apply(&"R2 U2 R2 U2 R2 U2".parse().unwrap());
+        let solutions = solver.solve_all(&cube).unwrap();
+        assert!(solutions.len() > 1);
+        for solution in solutions.iter() {
+            assert_eq!(solution.len(), 6);
+            assert!(cube.apply(solution).is_solved());
+        }
+    }
+
+    #[test]
+    fn test_search_should_report_progress_and_respect_max_length() {
+        let solver = OptimalSolver::new(databases());
+        let cube = RubiksCube::new().apply(&"R U F D".parse().unwrap());
+        let mut reports = Vec::new();
+        let solutions = solver
+            .search(&cube, false, |progress| reports.push(*progress))
+            .unwrap();
+        let last = reports.last().unwrap();
+        assert_eq!(last.depth, 4);
+        assert_eq!(last.solutions, solutions.len());
+        assert!(last.nodes > 0);
+        assert_eq!(solver.max_length(3).solve(&cube), Err(SolveError::NotFound));
+    }
+
+    #[test]
+    fn test_databases_should_load_what_was_saved() {
+        let path = std::env::temp_dir().join("rubiks-cube-pattern-test.db");
+        databases().save(&path).unwrap();
+        assert_eq!(PatternDatabases::load(&path).unwrap(), *databases());
+        let mut bytes = std::fs::read(&path).unwrap();
+        bytes[8..10].copy_from_slice(&[8, 12]);
+        std::fs::write(&path, &bytes).unwrap();
+        let error = PatternDatabases::load(&path).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,23 @@
+#[derive(PartialEq, Debug, Clone)]
+pub struct ParseError {
+    pub position: usize,
+    pub token: String,
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "unexpected token `{}` at position {}",
+            self.token, self.position
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl ParseError {
+    pub fn new(position: usize, token: String) -> ParseError {
+        ParseError { position, token }
+    }
+}
@@ -0,0 +1,433 @@
+use crate::CubieCube;
+use crate::Move;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Error, ErrorKind, Read, Write};
+use std::path::Path;
+
+const MAGIC: &[u8; 8] = b"RCPATDB1";
+const UNKNOWN: u8 = u8::MAX;
+
+// Position and orientation of each tracked piece, in piece order.
+type Pieces = [(usize, u8); 12];
+
+// Lower bounds on the number of moves left, read from exhaustive tables of
+// how far each arrangement of some pieces is from home: one for the first
+// corners and two for the first and last edges.
+#[derive(PartialEq, Debug, Clone)]
+pub struct PatternDatabases {
+    corners: PatternDatabase,
+    first_edges: PatternDatabase,
+    last_edges: PatternDatabase,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub(crate) struct PatternDatabase {
+    kind: PieceKind,
+    pieces: Vec<usize>,
+    depths: Vec<u8>,
+}
+
+// Exact distances from home of some corners and edges together, over any
+// set of moves given as the cubies they turn. Methods building blocks of
+// pieces follow it down to 0.
+#[derive(PartialEq, Debug, Clone)]
+pub(crate) struct BlockTable {
+    corners: PatternDatabase,
+    edges: PatternDatabase,
+    moves: Vec<CubieCube>,
+    corner_moves: Vec<u16>,
+    edge_moves: Vec<u16>,
+    depths: Vec<u8>,
+}
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub(crate) enum PieceKind {
+    Corner,
+    Edge,
+}
+
+impl PatternDatabases {
+    // Uses as many pieces as fit in `memory_budget` bytes, a third of it for
+    // each table. A table of no pieces still has its one entry, so the
+    // databases never take less than 3 bytes, whatever the budget.
+    pub fn new(memory_budget: usize) -> PatternDatabases {
+        let corner_pieces = PieceKind::Corner.pieces_within(memory_budget / 3);
+        let edge_pieces = PieceKind::Edge.pieces_within(memory_budget / 3);
+        let moves: Vec<usize> = (0..18).collect();
+        PatternDatabases {
+            corners: PatternDatabase::new(PieceKind::Corner, (0..corner_pieces).collect(), &moves),
+            first_edges: PatternDatabase::new(PieceKind::Edge, (0..edge_pieces).collect(), &moves),
+            last_edges: PatternDatabase::new(
+                PieceKind::Edge,
+                (12 - edge_pieces..12).collect(),
+                &moves,
+            ),
+        }
+    }
+
+    pub fn corner_pieces(&self) -> usize {
+        self.corners.pieces.len()
+    }
+
+    pub fn edge_pieces(&self) -> usize {
+        self.first_edges.pieces.len()
+    }
+
+    pub fn memory_usage(&self) -> usize {
+        self.tables().iter().map(|table| table.depths.len()).sum()
+    }
+
+    // Loads the databases saved at `path`, or generates them within
+    // `memory_budget` and saves them there if the file does not exist yet.
+    pub fn load_or_generate<P: AsRef<Path>>(
+        path: P,
+        memory_budget: usize,
+    ) -> std::io::Result<PatternDatabases> {
+        if path.as_ref().exists() {
+            return PatternDatabases::load(path);
+        }
+        let databases = PatternDatabases::new(memory_budget);
+        databases.save(path)?;
+        Ok(databases)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[self.corner_pieces() as u8, self.edge_pieces() as u8])?;
+        for table in self.tables().iter() {
+            writer.write_all(&table.depths)?;
+        }
+        writer.flush()
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> std::io::Result<PatternDatabases> {
+        let invalid = |message| Error::new(ErrorKind::InvalidData, message);
+        let file = File::open(path)?;
+        let length = file.metadata()?.len();
+        let mut reader = BufReader::new(file);
+        let mut header = [0; 10];
+        reader.read_exact(&mut header)?;
+        if &header[..8] != MAGIC {
+            return Err(invalid("not a pattern database file"));
+        }
+        let (corner_pieces, edge_pieces) = (header[8] as usize, header[9] as usize);
+        if corner_pieces > 8 || edge_pieces > 12 {
+            return Err(invalid("pattern database has too many pieces"));
+        }
+        // Checked before the tables are made, so a damaged header cannot ask
+        // for more memory than the file holds.
+        let tables = PieceKind::Corner.size(corner_pieces) + 2 * PieceKind::Edge.size(edge_pieces);
+        if length != (header.len() + tables) as u64 {
+            return Err(invalid("pattern database size does not match its pieces"));
+        }
+        let mut databases = PatternDatabases {
+            corners: PatternDatabase::empty(PieceKind::Corner, (0..corner_pieces).collect()),
+            first_edges: PatternDatabase::empty(PieceKind::Edge, (0..edge_pieces).collect()),
+            last_edges: PatternDatabase::empty(PieceKind::Edge, (12 - edge_pieces..12).collect()),
+        };
+        for table in databases.tables_mut().iter_mut() {
+            reader.read_exact(&mut table.depths)?;
+        }
+        if reader.read(&mut [0])? != 0 {
+            return Err(invalid("trailing data after pattern databases"));
+        }
+        Ok(databases)
+    }
+
+    pub fn lower_bound(&self, cube: &CubieCube) -> u8 {
+        self.tables()
+            .iter()
+            .map(|table| table.depth(cube))
+            .max()
+            .unwrap()
+    }
+
+    fn tables(&self) -> [&PatternDatabase; 3] {
+        [&self.corners, &self.first_edges, &self.last_edges]
+    }
+
+    fn tables_mut(&mut self) -> [&mut PatternDatabase; 3] {
+        [
+            &mut self.corners,
+            &mut self.first_edges,
+            &mut self.last_edges,
+        ]
+    }
+}
+
+impl PieceKind {
+    fn slots(self) -> usize {
+        match self {
+            PieceKind::Corner => 8,
+            PieceKind::Edge => 12,
+        }
+    }
+
+    fn orientations(self) -> usize {
+        match self {
+            PieceKind::Corner => 3,
+            PieceKind::Edge => 2,
+        }
+    }
+
+    // With every piece tracked the last orientation follows from the others.
+    fn size(self, pieces: usize) -> usize {
+        let placements: usize = (self.slots() - pieces + 1..=self.slots()).product();
+        let oriented = pieces.min(self.slots() - 1) as u32;
+        placements * self.orientations().pow(oriented)
+    }
+
+    fn pieces_within(self, memory_budget: usize) -> usize {
+        (0..=self.slots())
+            .take_while(|pieces| self.size(*pieces) <= memory_budget)
+            .last()
+            .unwrap_or(0)
+    }
+
+    fn positions(self, cube: &CubieCube) -> Pieces {
+        let mut positions = [(0, 0); 12];
+        match self {
+            PieceKind::Corner => {
+                for (i, corner) in cube.corner_permutation.iter().enumerate() {
+                    positions[*corner as usize] = (i, cube.corner_orientation[i]);
+                }
+            }
+            PieceKind::Edge => {
+                for (i, edge) in cube.edge_permutation.iter().enumerate() {
+                    positions[*edge as usize] = (i, cube.edge_orientation[i]);
+                }
+            }
+        }
+        positions
+    }
+
+    // For every move, where a piece at each position goes and how much its
+    // orientation changes.
+    fn move_targets(self) -> Vec<Vec<(usize, u8)>> {
+        (0..18)
+            .map(|m| self.targets(&CubieCube::new().apply_move(Move::face_turn(m))))
+            .collect()
+    }
+
+    fn targets(self, turn: &CubieCube) -> Vec<(usize, u8)> {
+        let mut targets = vec![(0, 0); self.slots()];
+        for i in 0..self.slots() {
+            match self {
+                PieceKind::Corner => {
+                    targets[turn.corner_permutation[i] as usize] = (i, turn.corner_orientation[i]);
+                }
+                PieceKind::Edge => {
+                    targets[turn.edge_permutation[i] as usize] = (i, turn.edge_orientation[i]);
+                }
+            }
+        }
+        targets
+    }
+}
+
+impl PatternDatabase {
+    fn empty(kind: PieceKind, pieces: Vec<usize>) -> PatternDatabase {
+        PatternDatabase {
+            kind,
+            depths: vec![0; kind.size(pieces.len())],
+            pieces,
+        }
+    }
+
+    // Breadth first search from home over the given face turns, one layer
+    // of depths at a time. Each layer is found by scanning the table for the
+    // one before, so no memory beyond the table is needed.
+    pub(crate) fn new(kind: PieceKind, pieces: Vec<usize>, moves: &[usize]) -> PatternDatabase {
+        let mut database = PatternDatabase::empty(kind, pieces);
+        database
+            .depths
+            .iter_mut()
+            .for_each(|depth| *depth = UNKNOWN);
+        let all_targets = kind.move_targets();
+        let targets: Vec<&Vec<(usize, u8)>> = moves.iter().map(|m| &all_targets[*m]).collect();
+        let mut home = [(0, 0); 12];
+        for (piece, position) in home.iter_mut().zip(database.pieces.iter()) {
+            *piece = (*position, 0);
+        }
+        let home = database.index(&home);
+        database.depths[home] = 0;
+        let mut depth = 0;
+        let mut found = true;
+        while found {
+            found = false;
+            for index in 0..database.depths.len() {
+                if database.depths[index] != depth {
+                    continue;
+                }
+                let pieces = database.pieces_at(index);
+                for move_targets in targets.iter() {
+                    let moved = database.moved(&pieces, move_targets);
+                    let next_index = database.index(&moved);
+                    if database.depths[next_index] == UNKNOWN {
+                        database.depths[next_index] = depth + 1;
+                        found = true;
+                    }
+                }
+            }
+            depth += 1;
+        }
+        database
+    }
+
+    pub(crate) fn depth(&self, cube: &CubieCube) -> u8 {
+        self.depths[self.index(&self.tracked(cube))]
+    }
+
+    fn tracked(&self, cube: &CubieCube) -> Pieces {
+        let positions = self.kind.positions(cube);
+        let mut pieces = [(0, 0); 12];
+        for (tracked, piece) in pieces.iter_mut().zip(self.pieces.iter()) {
+            *tracked = positions[*piece];
+        }
+        pieces
+    }
+
+    fn moved(&self, pieces: &Pieces, targets: &[(usize, u8)]) -> Pieces {
+        let mut moved = *pieces;
+        for (position, orientation) in moved.iter_mut().take(self.pieces.len()) {
+            let (target, twist) = targets[*position];
+            *position = target;
+            *orientation = (*orientation + twist) % self.kind.orientations() as u8;
+        }
+        moved
+    }
+
+    // Numbers the positions of the tracked pieces among the free slots, then
+    // their orientations.
+    fn index(&self, pieces: &Pieces) -> usize {
+        let slots = self.kind.slots();
+        let mut index = 0;
+        let mut used = 0u32;
+        let count = self.pieces.len();
+        for (i, (position, _)) in pieces.iter().enumerate().take(count) {
+            let free_before = position - (used & ((1 << position) - 1)).count_ones() as usize;
+            index = index * (slots - i) + free_before;
+            used |= 1 << position;
+        }
+        for (_, orientation) in pieces.iter().take(count.min(slots - 1)) {
+            index = index * self.kind.orientations() + *orientation as usize;
+        }
+        index
+    }
+
+    fn pieces_at(&self, index: usize) -> Pieces {
+        let slots = self.kind.slots();
+        let orientations = self.kind.orientations();
+        let count = self.pieces.len();
+        let oriented = count.min(slots - 1);
+        let mut rest = index;
+        let mut pieces = [(0, 0); 12];
+        let mut twist = 0;
+        for i in (0..oriented).rev() {
+            pieces[i].1 = (rest % orientations) as u8;
+            twist += orientations - rest % orientations;
+            rest /= orientations;
+        }
+        if oriented < count {
+            pieces[oriented].1 = (twist % orientations) as u8;
+        }
+        let mut free_before = [0; 12];
+        for i in (0..count).rev() {
+            free_before[i] = rest % (slots - i);
+            rest /= slots - i;
+        }
+        let mut used = [false; 12];
+        for (piece, free) in pieces.iter_mut().zip(free_before.iter()).take(count) {
+            let position = (0..slots).filter(|slot| !used[*slot]).nth(*free).unwrap();
+            used[position] = true;
+            piece.0 = position;
+        }
+        pieces
+    }
+}
+
+impl BlockTable {
+    // The tables of the pieces alone only number their arrangements, the
+    // search goes over every arrangement of all of them.
+    pub(crate) fn new(corners: Vec<usize>, edges: Vec<usize>, moves: Vec<CubieCube>) -> BlockTable {
+        let corners = PatternDatabase::empty(PieceKind::Corner, corners);
+        let edges = PatternDatabase::empty(PieceKind::Edge, edges);
+        let corner_moves = BlockTable::move_table(&corners, &moves);
+        let edge_moves = BlockTable::move_table(&edges, &moves);
+        let mut table = BlockTable {
+            depths: vec![UNKNOWN; corners.depths.len() * edges.depths.len()],
+            corners,
+            edges,
+            moves,
+            corner_moves,
+            edge_moves,
+        };
+        let home = table.index(&CubieCube::new());
+        table.depths[home] = 0;
+        let mut layer = vec![home];
+        let mut depth = 0;
+        while !layer.is_empty() {
+            depth += 1;
+            let mut next_layer = Vec::new();
+            for index in layer {
+                for m in 0..table.moves.len() {
+                    let next_index = table.next(index, m);
+                    if table.depths[next_index] == UNKNOWN {
+                        table.depths[next_index] = depth;
+                        next_layer.push(next_index);
+                    }
+                }
+            }
+            layer = next_layer;
+        }
+        table
+    }
+
+    // The moves, as indices in the list the table was made with, of a
+    // shortest way home for the pieces.
+    pub(crate) fn solve(&self, cube: &CubieCube) -> Vec<usize> {
+        let mut index = self.index(cube);
+        let mut moves = Vec::new();
+        while self.depths[index] > 0 {
+            let m = (0..self.moves.len())
+                .find(|m| self.depths[self.next(index, *m)] < self.depths[index])
+                .unwrap();
+            index = self.next(index, m);
+            moves.push(m);
+        }
+        moves
+    }
+
+    fn index(&self, cube: &CubieCube) -> usize {
+        let corners = self.corners.index(&self.corners.tracked(cube));
+        let edges = self.edges.index(&self.edges.tracked(cube));
+        corners * self.edges.depths.len() + edges
+    }
+
+    fn next(&self, index: usize, m: usize) -> usize {
+        let edge_count = self.edges.depths.len();
+        let count = self.moves.len();
+        let corners = self.corner_moves[index / edge_count * count + m] as usize;
+        let edges = self.edge_moves[index % edge_count * count + m] as usize;
+        corners * edge_count + edges
+    }
+
+    fn move_table(numbering: &PatternDatabase, moves: &[CubieCube]) -> Vec<u16> {
+        let targets: Vec<Vec<(usize, u8)>> = moves
+            .iter()
+            .map(|turn| numbering.kind.targets(turn))
+            .collect();
+        (0..numbering.depths.len())
+            .flat_map(|index| {
+                let pieces = numbering.pieces_at(index);
+                targets
+                    .iter()
+                    .map(move |move_targets| {
+                        numbering.index(&numbering.moved(&pieces, move_targets)) as u16
+                    })
+                    .collect::<Vec<u16>>()
+            })
+            .collect()
+    }
+}
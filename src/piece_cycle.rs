@@ -0,0 +1,22 @@
+// Pieces moving into each other's places, each to the next and the last to
+// the first. Positions are named by the faces they touch, as "UFR" or "UF".
+#[derive(PartialEq, Debug, Clone)]
+pub struct PieceCycle {
+    pub positions: Vec<String>,
+    // Twist or flip a piece gains going once round the cycle, in thirds or
+    // halves of a turn.
+    pub orientation: u8,
+    // Times the algorithm is applied before the pieces are back in place
+    // and oriented.
+    pub order: usize,
+}
+
+impl std::fmt::Display for PieceCycle {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "({})", self.positions.join(" "))?;
+        if self.orientation != 0 {
+            write!(f, "{}", "+".repeat(self.orientation as usize))?;
+        }
+        Ok(())
+    }
+}
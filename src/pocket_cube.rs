@@ -0,0 +1,200 @@
+use crate::cubie_cube::{CORNER_FACELETS, FACES};
+use crate::Algorithm;
+use crate::ColorFacet;
+use crate::Corner;
+use crate::Cube;
+use crate::CubieCube;
+use crate::FaceGrid;
+use crate::Facet;
+use crate::Move;
+use crate::RotationDirection;
+use crate::RubiksCube;
+use crate::ValidationError;
+
+// A 2x2 cube, kept as its eight corners numbered and oriented as in
+// `CubieCube`. Without centers, turning a face and the opposite one the
+// other way is the same as rotating the whole cube.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct PocketCube {
+    pub corner_permutation: [Corner; 8],
+    pub corner_orientation: [u8; 8],
+}
+
+impl Default for PocketCube {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PocketCube {
+    pub fn new() -> PocketCube {
+        PocketCube::from_cubie_cube(&CubieCube::new())
+    }
+
+    // Every sequence of moves keeps each of the eight corners once, with
+    // twists adding up to a multiple of 3.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        for corner in Corner::iterator() {
+            match self
+                .corner_permutation
+                .iter()
+                .filter(|c| *c == corner)
+                .count()
+            {
+                0 => return Err(ValidationError::MissingCorner(*corner)),
+                1 => {}
+                _ => return Err(ValidationError::DuplicateCorner(*corner)),
+            }
+        }
+        let twist: u32 = self.corner_orientation.iter().map(|o| *o as u32).sum();
+        if self.corner_orientation.iter().any(|o| *o > 2) || twist % 3 != 0 {
+            return Err(ValidationError::TwistedCorner);
+        }
+        Ok(())
+    }
+
+    // Whether every face is one color, however the cube is held. Never the
+    // case for a cube that does not validate.
+    pub fn is_solved(&self) -> bool {
+        self.validate().is_ok()
+            && self
+                .held_home()
+                .is_some_and(|(_, held)| held == PocketCube::new())
+    }
+
+    pub fn rotate(self, face: ColorFacet, direction: RotationDirection) -> PocketCube {
+        self.apply_move(Move::Face(face, direction))
+    }
+
+    pub fn apply(self, algorithm: &Algorithm) -> PocketCube {
+        algorithm
+            .moves
+            .iter()
+            .fold(self, |cube, cube_move| cube.apply_move(*cube_move))
+    }
+
+    // Slice moves leave a 2x2 as it is, and wide moves turn it whole.
+    pub fn apply_move(self, cube_move: Move) -> PocketCube {
+        match cube_move {
+            Move::Face(_, _) => {
+                PocketCube::from_cubie_cube(&self.to_cubie_cube().apply_move(cube_move))
+            }
+            Move::Slice(_, _) => self,
+            Move::Wide(face, direction) => self
+                .rotate(face, direction)
+                .rotate(face.opposite(), direction.opposite()),
+            Move::Rotation(axis, direction) => self.apply_move(Move::Wide(axis.face(), direction)),
+        }
+    }
+
+    // The rotations taking the cube to where the down back left corner is
+    // at home, and the cube held that way. None when no way of holding the
+    // cube does, as when that corner is missing.
+    pub(crate) fn held_home(&self) -> Option<(Algorithm, PocketCube)> {
+        RubiksCube::orientations()
+            .into_iter()
+            .map(|rotations| {
+                let held = self.apply(&rotations);
+                (rotations, held)
+            })
+            .find(|(_, held)| {
+                held.corner_permutation[Corner::Dbl as usize] == Corner::Dbl
+                    && held.corner_orientation[Corner::Dbl as usize] == 0
+            })
+    }
+
+    fn from_cubie_cube(cube: &CubieCube) -> PocketCube {
+        PocketCube {
+            corner_permutation: cube.corner_permutation,
+            corner_orientation: cube.corner_orientation,
+        }
+    }
+
+    fn to_cubie_cube(self) -> CubieCube {
+        CubieCube {
+            corner_permutation: self.corner_permutation,
+            corner_orientation: self.corner_orientation,
+            ..CubieCube::new()
+        }
+    }
+}
+
+// The facets of a 2x2 face are the corner facets of a 3x3 one.
+impl From<PocketCube> for Cube<2> {
+    fn from(cube: PocketCube) -> Cube<2> {
+        let mut faces: Vec<FaceGrid<2>> = FACES.iter().map(|face| FaceGrid::new(*face)).collect();
+        for (position, facelets) in CORNER_FACELETS.iter().enumerate() {
+            let piece = cube.corner_permutation[position] as usize;
+            let orientation = cube.corner_orientation[position] as usize;
+            for (n, (face, facelet)) in CORNER_FACELETS[piece].iter().enumerate() {
+                let (row, column) = grid_position(*facelet);
+                let facet = Facet::new(FACES[*face], (row * 2 + column + 1) as u8);
+                let (to_face, to_facelet) = facelets[(n + orientation) % 3];
+                let (row, column) = grid_position(to_facelet);
+                faces[to_face].facets[row][column] = facet;
+            }
+        }
+        FACES
+            .iter()
+            .zip(faces)
+            .fold(Cube::new(), |result, (face, grid)| {
+                result.with_face(*face, grid)
+            })
+    }
+}
+
+// Row and column on a 2x2 face of a corner facelet of a 3x3 face.
+fn grid_position(facelet: usize) -> (usize, usize) {
+    (facelet / 6, facelet % 3 / 2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pocket_cube_should_turn_like_a_two_layer_cube() {
+        let algorithms = [
+            "R U R' U' F2 D' L B2",
+            "x y' z2 R U",
+            "r U2 l' d f' b2 u D",
+            "M E S R",
+        ];
+        for text in algorithms.iter() {
+            let algorithm: Algorithm = text.parse().unwrap();
+            let cube = PocketCube::new().apply(&algorithm);
+            assert_eq!(
+                Cube::from(cube),
+                Cube::<2>::new().apply(&algorithm),
+                "{}",
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn test_is_solved_should_ignore_how_the_cube_is_held() {
+        assert!(PocketCube::new().is_solved());
+        assert!(PocketCube::new()
+            .apply(&"x y R L'".parse().unwrap())
+            .is_solved());
+        assert!(!PocketCube::new().apply(&"R".parse().unwrap()).is_solved());
+        let (rotations, held) = PocketCube::new()
+            .apply(&"L D B".parse().unwrap())
+            .held_home()
+            .unwrap();
+        assert!(!rotations.is_empty());
+        assert_eq!(held.corner_permutation[Corner::Dbl as usize], Corner::Dbl);
+    }
+
+    #[test]
+    fn test_is_solved_should_be_false_for_invalid_cubes() {
+        let mut duplicated = PocketCube::new();
+        duplicated.corner_permutation = [Corner::Urf; 8];
+        assert!(!duplicated.is_solved());
+        assert_eq!(duplicated.held_home(), None);
+        let mut twisted = PocketCube::new();
+        twisted.corner_orientation[0] = u8::MAX;
+        assert!(!twisted.is_solved());
+    }
+}
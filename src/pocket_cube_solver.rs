@@ -0,0 +1,231 @@
+use crate::cube_move::held_face_turn;
+use crate::two_phase_tables::{depth_table, move_table, MOVE_COUNT};
+use crate::Algorithm;
+use crate::Corner;
+use crate::Move;
+use crate::PocketCube;
+use crate::Solution;
+use crate::SolutionStage;
+use crate::SolveError;
+use crate::ValidationError;
+use std::sync::OnceLock;
+
+// Turns of the up, right and front faces, numbered as in `Move::face_turn`.
+// They keep the down back left corner in place, which fixes how the cube
+// is held.
+const MOVES: [usize; 9] = [0, 1, 2, 3, 4, 5, 6, 7, 8];
+
+// Orders of the seven other corners, and twists of six of them.
+const PERMUTATIONS: usize = 5040;
+const TWISTS: usize = 729;
+
+// Corner positions other than down back left, the one left in place.
+const POSITIONS: [usize; 7] = [0, 1, 2, 3, 4, 5, 7];
+
+#[derive(PartialEq, Debug, Clone, Copy, Default)]
+pub struct PocketCubeSolver {}
+
+struct Tables {
+    permutation_moves: Vec<u16>,
+    twist_moves: Vec<u16>,
+    depths: Vec<u8>,
+}
+
+impl PocketCubeSolver {
+    pub fn new() -> PocketCubeSolver {
+        PocketCubeSolver {}
+    }
+
+    // How many half turn metric moves the cube is from solved.
+    pub fn distance(&self, cube: &PocketCube) -> Result<usize, SolveError> {
+        let (_, held) = held_home(cube)?;
+        Ok(tables().depths[index(&held)] as usize)
+    }
+
+    // An optimal solution turning the faces of the cube as it is held.
+    pub fn solve(&self, cube: &PocketCube) -> Result<Solution, SolveError> {
+        let (rotations, held) = held_home(cube)?;
+        let tables = tables();
+        let mut permutation = permutation(&held);
+        let mut twist = twist(&held);
+        let mut moves = Vec::new();
+        loop {
+            let depth = tables.depths[permutation * TWISTS + twist];
+            if depth == 0 {
+                break;
+            }
+            let (m, next) = MOVES
+                .iter()
+                .map(|m| {
+                    let next = (
+                        tables.permutation_moves[permutation * MOVE_COUNT + m] as usize,
+                        tables.twist_moves[twist * MOVE_COUNT + m] as usize,
+                    );
+                    (*m, next)
+                })
+                .find(|(_, (p, t))| tables.depths[p * TWISTS + t] < depth)
+                .unwrap();
+            moves.push(held_face_turn(&rotations, Move::face_turn(m)));
+            permutation = next.0;
+            twist = next.1;
+        }
+        let count = moves.len();
+        Ok(Solution {
+            stages: vec![SolutionStage {
+                name: "optimal".to_string(),
+                moves: Algorithm::new(moves),
+                description: format!("{} moves, the fewest in the half turn metric", count),
+            }],
+        })
+    }
+
+    // How many states are at each distance from solved, for all 3674160 of
+    // them. The last distance is God's number in the half turn metric.
+    pub fn distribution(&self) -> Vec<usize> {
+        let mut counts = Vec::new();
+        for depth in tables().depths.iter() {
+            let depth = *depth as usize;
+            if counts.len() <= depth {
+                counts.resize(depth + 1, 0);
+            }
+            counts[depth] += 1;
+        }
+        counts
+    }
+}
+
+// A cube that validates always has the down back left corner to hold at
+// home.
+fn held_home(cube: &PocketCube) -> Result<(Algorithm, PocketCube), SolveError> {
+    cube.validate()?;
+    let held = cube.held_home();
+    held.ok_or(SolveError::Unsolvable(ValidationError::MissingCorner(
+        Corner::Dbl,
+    )))
+}
+
+fn index(cube: &PocketCube) -> usize {
+    permutation(cube) * TWISTS + twist(cube)
+}
+
+fn permutation(cube: &PocketCube) -> usize {
+    let pieces: Vec<usize> = POSITIONS
+        .iter()
+        .map(|p| cube.corner_permutation[*p] as usize)
+        .collect();
+    (0..7).fold(0, |index, i| {
+        let smaller = pieces[i + 1..].iter().filter(|p| **p < pieces[i]).count();
+        index * (7 - i) + smaller
+    })
+}
+
+fn with_permutation(permutation: usize) -> PocketCube {
+    let mut cube = PocketCube::new();
+    let mut digits = [0; 7];
+    let mut rest = permutation;
+    for i in (0..7).rev() {
+        digits[i] = rest % (7 - i);
+        rest /= 7 - i;
+    }
+    let mut pieces: Vec<_> = POSITIONS
+        .iter()
+        .map(|p| cube.corner_permutation[*p])
+        .collect();
+    for (i, digit) in digits.iter().enumerate() {
+        cube.corner_permutation[POSITIONS[i]] = pieces.remove(*digit);
+    }
+    cube
+}
+
+fn twist(cube: &PocketCube) -> usize {
+    cube.corner_orientation[..6]
+        .iter()
+        .fold(0, |twist, o| twist * 3 + *o as usize)
+}
+
+fn with_twist(twist: usize) -> PocketCube {
+    let mut cube = PocketCube::new();
+    let mut rest = twist;
+    for i in (0..6).rev() {
+        cube.corner_orientation[i] = (rest % 3) as u8;
+        rest /= 3;
+    }
+    let sum: u8 = cube.corner_orientation[..6].iter().sum();
+    cube.corner_orientation[7] = (3 - sum % 3) % 3;
+    cube
+}
+
+fn tables() -> &'static Tables {
+    static TABLES: OnceLock<Tables> = OnceLock::new();
+    TABLES.get_or_init(|| {
+        let permutation_moves = move_table(PERMUTATIONS, &MOVES, |i, face, direction| {
+            permutation(&with_permutation(i).rotate(face, direction))
+        });
+        let twist_moves = move_table(TWISTS, &MOVES, |i, face, direction| {
+            twist(&with_twist(i).rotate(face, direction))
+        });
+        let depths = depth_table(PERMUTATIONS * TWISTS, &MOVES, |i, m| {
+            let permutation = permutation_moves[i / TWISTS * MOVE_COUNT + m] as usize;
+            let twist = twist_moves[i % TWISTS * MOVE_COUNT + m] as usize;
+            permutation * TWISTS + twist
+        });
+        Tables {
+            permutation_moves,
+            twist_moves,
+            depths,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ValidationError;
+
+    #[test]
+    fn test_distribution_should_match_the_known_counts() {
+        let distribution = PocketCubeSolver::new().distribution();
+        assert_eq!(
+            distribution,
+            vec![1, 9, 54, 321, 1847, 9992, 50136, 227536, 870072, 1887748, 623800, 2644]
+        );
+    }
+
+    #[test]
+    fn test_solve_should_find_optimal_solutions() {
+        let solver = PocketCubeSolver::new();
+        let scrambles = [
+            ("", Some(0)),
+            ("R", Some(1)),
+            ("R U R' U'", Some(4)),
+            ("L D B2 x R' y F U2 L'", None),
+            (
+                "F2 D' L B2 R' U2 F' D2 L2 B R2 U' F L' D B' R U2 L F'",
+                None,
+            ),
+        ];
+        for (text, length) in scrambles.iter() {
+            let cube = PocketCube::new().apply(&text.parse().unwrap());
+            let solution = solver.solve(&cube).unwrap();
+            assert!(cube.apply(&solution.algorithm()).is_solved(), "{}", text);
+            assert_eq!(Ok(solution.len()), solver.distance(&cube));
+            if let Some(length) = length {
+                assert_eq!(solution.len(), *length);
+            }
+            assert!(solution.len() <= 11);
+        }
+    }
+
+    #[test]
+    fn test_solve_should_refuse_unsolvable_cubes() {
+        let solver = PocketCubeSolver::new();
+        let mut twisted = PocketCube::new();
+        twisted.corner_orientation[0] = 1;
+        let unsolvable = SolveError::Unsolvable(ValidationError::TwistedCorner);
+        assert_eq!(solver.solve(&twisted), Err(unsolvable));
+        assert_eq!(solver.distance(&twisted), Err(unsolvable));
+        let mut duplicated = PocketCube::new();
+        duplicated.corner_permutation[0] = duplicated.corner_permutation[1];
+        assert!(solver.solve(&duplicated).is_err());
+    }
+}
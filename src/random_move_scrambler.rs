@@ -0,0 +1,118 @@
+use crate::random::Random;
+use crate::Algorithm;
+use crate::ColorFacet;
+use crate::Move;
+use crate::RotationDirection;
+use crate::RubiksCube;
+use crate::Scramble;
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct RandomMoveScrambler {
+    pub length: usize,
+    random: Random,
+}
+
+impl Default for RandomMoveScrambler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RandomMoveScrambler {
+    pub fn new() -> RandomMoveScrambler {
+        RandomMoveScrambler::with_random(Random::from_time())
+    }
+
+    // The same seed gives the same scrambles, in the same order.
+    pub fn with_seed(seed: u64) -> RandomMoveScrambler {
+        RandomMoveScrambler::with_random(Random::new(seed))
+    }
+
+    fn with_random(random: Random) -> RandomMoveScrambler {
+        RandomMoveScrambler { length: 25, random }
+    }
+
+    pub fn length(self, length: usize) -> RandomMoveScrambler {
+        RandomMoveScrambler { length, ..self }
+    }
+
+    // Random face turns, never turning the face turned just before, nor
+    // going back to a face after turning only the opposite one, as in
+    // R L R, which would be shorter as R2 L.
+    pub fn scramble(&mut self) -> Scramble {
+        let faces: Vec<ColorFacet> = ColorFacet::iterator().cloned().collect();
+        let directions: Vec<RotationDirection> = RotationDirection::iterator().cloned().collect();
+        let mut turned: Vec<ColorFacet> = Vec::new();
+        let mut moves: Vec<Move> = Vec::new();
+        while moves.len() < self.length {
+            let face = faces[self.random.below(faces.len())];
+            let direction = directions[self.random.below(directions.len())];
+            if allowed(&turned, face) {
+                turned.push(face);
+                moves.push(Move::Face(face, direction));
+            }
+        }
+        let algorithm = Algorithm::new(moves);
+        let cube = RubiksCube::new().apply(&algorithm);
+        Scramble { algorithm, cube }
+    }
+}
+
+// Whether the face may be turned after the faces turned so far.
+fn allowed(turned: &[ColorFacet], next: ColorFacet) -> bool {
+    match turned {
+        [.., before, last] if *last == next.opposite() => *before != next,
+        [.., last] => *last != next,
+        [] => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scramble_should_follow_the_move_rules() {
+        let mut scrambler = RandomMoveScrambler::with_seed(3).length(200);
+        let scramble = scrambler.scramble();
+        assert_eq!(scramble.algorithm.len(), 200);
+        assert_eq!(scramble.cube, RubiksCube::new().apply(&scramble.algorithm));
+        let faces: Vec<ColorFacet> = scramble
+            .algorithm
+            .moves
+            .iter()
+            .filter_map(|cube_move| match cube_move {
+                Move::Face(face, _) => Some(*face),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(faces.len(), 200);
+        for pair in faces.windows(2) {
+            assert!(pair[0] != pair[1]);
+        }
+        for triple in faces.windows(3) {
+            assert!(!(triple[0] == triple[2] && triple[1] == triple[0].opposite()));
+        }
+    }
+
+    #[test]
+    fn test_scramble_should_repeat_with_the_same_seed() {
+        let first = RandomMoveScrambler::with_seed(5).length(20).scramble();
+        let second = RandomMoveScrambler::with_seed(5).length(20).scramble();
+        let other = RandomMoveScrambler::with_seed(6).length(20).scramble();
+        assert_eq!(first, second);
+        assert_ne!(first, other);
+        assert_eq!(first.to_string().split(' ').count(), 20);
+        assert_eq!(
+            first.to_string().parse::<Algorithm>().unwrap(),
+            first.algorithm
+        );
+    }
+
+    #[test]
+    fn test_scramble_should_allow_no_moves() {
+        let scramble = RandomMoveScrambler::with_seed(1).length(0).scramble();
+        assert!(scramble.algorithm.is_empty());
+        assert!(scramble.cube.is_solved());
+    }
+}
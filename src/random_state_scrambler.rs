@@ -0,0 +1,122 @@
+use crate::random::Random;
+use crate::Algorithm;
+use crate::CubieCube;
+use crate::RubiksCube;
+use crate::Scramble;
+use crate::TwoPhaseSolver;
+use std::time::Duration;
+
+#[derive(Debug, Clone)]
+pub struct RandomStateScrambler {
+    random: Random,
+    solver: TwoPhaseSolver,
+}
+
+impl Default for RandomStateScrambler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RandomStateScrambler {
+    pub fn new() -> RandomStateScrambler {
+        RandomStateScrambler::with_random(Random::from_time())
+    }
+
+    // The same seed gives the same scrambles, in the same order.
+    pub fn with_seed(seed: u64) -> RandomStateScrambler {
+        RandomStateScrambler::with_random(Random::new(seed))
+    }
+
+    fn with_random(random: Random) -> RandomStateScrambler {
+        RandomStateScrambler {
+            random,
+            solver: TwoPhaseSolver::new(),
+        }
+    }
+
+    // Every solvable state is equally likely: the pieces are shuffled and
+    // twisted at random, then two edges are swapped if the corner and edge
+    // permutations do not have the same parity.
+    pub fn random_state(&mut self) -> CubieCube {
+        let mut cube = CubieCube::new();
+        self.random.shuffle(&mut cube.corner_permutation);
+        self.random.shuffle(&mut cube.edge_permutation);
+        if cube.corner_parity() != cube.edge_parity() {
+            cube.edge_permutation.swap(10, 11);
+        }
+        cube.with_twist(self.random.below(2187))
+            .with_flip(self.random.below(2048))
+    }
+
+    // The scramble is the inverse of a two-phase solution of a random
+    // state, so applying it to a solved cube gives that state. Phase 1 never
+    // needs more than 12 moves and phase 2 more than 18, so without a
+    // timeout the second search always finds a solution.
+    pub fn scramble(&mut self) -> Scramble {
+        let state = self.random_state();
+        let solution = self
+            .solver
+            .solve_cubie_cube(&state)
+            .or_else(|_| {
+                let solver = self.solver.clone().max_length(30);
+                solver.timeout(Duration::MAX).solve_cubie_cube(&state)
+            })
+            .unwrap();
+        let moves = solution.moves.iter().rev().map(|m| m.inverse()).collect();
+        let algorithm = Algorithm::new(moves);
+        let cube = RubiksCube::new().apply(&algorithm);
+        Scramble { algorithm, cube }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scramble_should_reach_the_random_state() {
+        let mut scrambler = RandomStateScrambler::with_seed(42);
+        let mut states = RandomStateScrambler::with_seed(42);
+        for _ in 0..5 {
+            let scramble = scrambler.scramble();
+            let state = states.random_state();
+            assert_eq!(scramble.cube, state.to_rubiks_cube());
+            assert_eq!(scramble.cube, RubiksCube::new().apply(&scramble.algorithm));
+            assert!(scramble.algorithm.len() <= 21);
+        }
+    }
+
+    #[test]
+    fn test_scramble_should_not_depend_on_the_timeout() {
+        let mut scrambler = RandomStateScrambler::with_seed(3);
+        scrambler.solver = scrambler.solver.timeout(Duration::from_nanos(1));
+        let scramble = scrambler.scramble();
+        assert_eq!(scramble.cube, RubiksCube::new().apply(&scramble.algorithm));
+        assert!(scramble.algorithm.len() <= 30);
+    }
+
+    #[test]
+    fn test_scramble_should_repeat_with_the_same_seed() {
+        let first = RandomStateScrambler::with_seed(7).scramble();
+        let second = RandomStateScrambler::with_seed(7).scramble();
+        let other = RandomStateScrambler::with_seed(8).scramble();
+        assert_eq!(first, second);
+        assert_ne!(first, other);
+    }
+
+    #[test]
+    fn test_random_state_should_be_valid_and_spread_out() {
+        let mut scrambler = RandomStateScrambler::with_seed(1);
+        let mut twists = [0; 3];
+        let mut corners = [0; 8];
+        for _ in 0..6000 {
+            let cube = scrambler.random_state();
+            assert_eq!(cube.validate(), Ok(()));
+            twists[cube.corner_orientation[0] as usize] += 1;
+            corners[cube.corner_permutation[0] as usize] += 1;
+        }
+        assert!(twists.iter().all(|count| (1800..2200).contains(count)));
+        assert!(corners.iter().all(|count| (600..900).contains(count)));
+    }
+}
@@ -0,0 +1,630 @@
+use crate::cube::{face_index, position};
+use crate::Algorithm;
+use crate::ColorFacet;
+use crate::ColorScheme;
+use crate::Cube;
+use crate::FaceletError;
+use crate::Move;
+use crate::RotationDirection;
+use crate::RubiksCube;
+use crate::Solution;
+use crate::SolutionStage;
+use crate::SolveError;
+use crate::TwoPhaseSolver;
+use crate::ValidationError;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+// Flips the up front edge of a reduced 4x4, keeping centers and the other
+// edges paired.
+const OLL_PARITY: &str = "Rw U2 x Rw U2 Rw U2 Rw' U2 Lw U2 Rw' U2 Rw U2 Rw' U2 Rw'";
+// Swaps the up front and up back edges, r2 U2 r2 Uw2 r2 Uw2 with the inner
+// slice r2 written as Rw2 R2.
+const PLL_PARITY: &str = "Rw2 R2 U2 Rw2 R2 Uw2 Rw2 R2 Uw2";
+
+// Each pairs the wings of an edge in the middle layer by turning the inner
+// layers, stores it away with outer turns and turns the inner layers back.
+const PAIRING: [&str; 8] = [
+    "Dw R U R' F R' F' R Dw'",
+    "Dw' R U R' F R' F' R Dw",
+    "Uw R U R' F R' F' R Uw'",
+    "Uw' R U R' F R' F' R Uw",
+    "Dw R F' U R' F Dw'",
+    "Dw' R F' U R' F Dw",
+    "Uw R F' U R' F Uw'",
+    "Uw' R F' U R' F Uw",
+];
+
+const DIRECTIONS: [RotationDirection; 3] = [
+    RotationDirection::Clockwise,
+    RotationDirection::HalfTurn,
+    RotationDirection::Anticlockwise,
+];
+
+// Centers the first stage takes to the up and down faces.
+const UP_DOWN: [ColorFacet; 2] = [ColorFacet::White, ColorFacet::Yellow];
+
+const STICKERS: usize = 96;
+
+// Rows and columns of a 4x4 face standing for those of a 3x3 one.
+const LINES: [usize; 3] = [0, 1, 3];
+
+#[derive(Debug, Clone)]
+pub struct ReductionSolver {
+    solver: TwoPhaseSolver,
+}
+
+impl Default for ReductionSolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// The colors of a 4x4, each face by row then column, faces in the order of
+// `ColorFacet::iterator`.
+type Stickers = [ColorFacet; STICKERS];
+
+// A turn of one layer, with where each sticker comes from.
+struct Turn {
+    face: ColorFacet,
+    depth: usize,
+    direction: RotationDirection,
+    sources: [u8; STICKERS],
+}
+
+struct CenterStage {
+    groups: Vec<Vec<ColorFacet>>,
+    turns: Vec<usize>,
+    depths: HashMap<u128, u8>,
+}
+
+impl ReductionSolver {
+    pub fn new() -> ReductionSolver {
+        ReductionSolver {
+            solver: TwoPhaseSolver::new(),
+        }
+    }
+
+    // Solves the centers, pairs the edges, fixes the parities a 3x3 cannot
+    // have and finishes the cube as a 3x3, turning only outer layers.
+    pub fn solve(&self, cube: &Cube<4>) -> Result<Solution, SolveError> {
+        let mut solving = Solving {
+            stickers: stickers(cube),
+            turns: Vec::new(),
+            stages: Vec::new(),
+        };
+        solving.solve_centers()?;
+        solving.pair_edges()?;
+        let reduced = solving.fix_parities()?;
+        let algorithm = self.solver.solve(&reduced)?;
+        for cube_move in algorithm.moves.iter() {
+            solving.apply_move(*cube_move);
+        }
+        solving.finish_stage("3x3", format!("{} moves as a 3x3", algorithm.len()));
+        Ok(Solution {
+            stages: solving.stages,
+        })
+    }
+}
+
+struct Solving {
+    stickers: Stickers,
+    turns: Vec<Move>,
+    stages: Vec<SolutionStage>,
+}
+
+impl Solving {
+    fn apply_turn(&mut self, turn: usize) {
+        let turn = &turns()[turn];
+        self.stickers = turn.apply(&self.stickers);
+        self.turns.extend(turn.moves());
+    }
+
+    fn apply_move(&mut self, cube_move: Move) {
+        for turn in move_turns(cube_move) {
+            self.apply_turn(turn);
+        }
+    }
+
+    fn finish_stage(&mut self, name: &str, description: String) {
+        let moves = Algorithm::new(std::mem::take(&mut self.turns));
+        self.stages.push(SolutionStage {
+            name: name.to_string(),
+            moves: moves.simplify().algorithm,
+            description,
+        });
+    }
+
+    // Up and down centers go to the up and down faces, then left and right
+    // ones to the left and right faces, then each center is finished with
+    // half turns of the other layers.
+    fn solve_centers(&mut self) -> Result<(), SolveError> {
+        for stage in center_stages().iter() {
+            loop {
+                let depth = stage.depth(&self.stickers).ok_or(SolveError::NotFound)?;
+                if depth == 0 {
+                    break;
+                }
+                let turn = *stage
+                    .turns
+                    .iter()
+                    .find(|t| stage.depth(&turns()[**t].apply(&self.stickers)) == Some(depth - 1))
+                    .ok_or(SolveError::NotFound)?;
+                self.apply_turn(turn);
+            }
+        }
+        let count = self.turns.len();
+        self.finish_stage(
+            "centers",
+            format!("{} layer turns to put every center on its face", count),
+        );
+        Ok(())
+    }
+
+    // Pairs edges one at a time with outer turns to set them up, then one
+    // of the pairing algorithms.
+    fn pair_edges(&mut self) -> Result<(), SolveError> {
+        let already = paired_edges(&self.stickers);
+        let pairing: Vec<Vec<usize>> = PAIRING
+            .iter()
+            .map(|text| {
+                let algorithm: Algorithm = text.parse().unwrap();
+                algorithm
+                    .moves
+                    .iter()
+                    .flat_map(|m| move_turns(*m))
+                    .collect()
+            })
+            .collect();
+        let outer: Vec<usize> = (0..turns().len())
+            .filter(|t| turns()[*t].depth == 0)
+            .collect();
+        let mut algorithms = 0;
+        while paired_edges(&self.stickers) < 12 {
+            let paired = paired_edges(&self.stickers);
+            let found = (0..=3).find_map(|length| {
+                setups(&outer, length).into_iter().find_map(|setup| {
+                    let stickers = setup
+                        .iter()
+                        .fold(self.stickers, |s, t| turns()[*t].apply(&s));
+                    pairing.iter().find_map(|algorithm| {
+                        let after = algorithm
+                            .iter()
+                            .fold(stickers, |s, t| turns()[*t].apply(&s));
+                        if centers_solved(&after) && paired_edges(&after) > paired {
+                            Some((setup.clone(), algorithm.clone()))
+                        } else {
+                            None
+                        }
+                    })
+                })
+            });
+            let (setup, algorithm) = found.ok_or(SolveError::NotFound)?;
+            for turn in setup.iter().chain(algorithm.iter()) {
+                self.apply_turn(*turn);
+            }
+            algorithms += 1;
+        }
+        self.finish_stage(
+            "edge pairing",
+            format!(
+                "{} edges already paired, {} pairing algorithms",
+                already, algorithms
+            ),
+        );
+        Ok(())
+    }
+
+    // A single flipped edge or two swapped edges cannot happen on a 3x3, and
+    // are fixed here before solving the cube as one.
+    fn fix_parities(&mut self) -> Result<RubiksCube, SolveError> {
+        let mut reduced = reduced_cube(&self.stickers);
+        let oll = if reduced == Err(ValidationError::FlippedEdge) {
+            self.apply_algorithm(OLL_PARITY);
+            reduced = reduced_cube(&self.stickers);
+            format!("one edge flipped, fixed with {}", OLL_PARITY)
+        } else {
+            "no OLL parity".to_string()
+        };
+        self.finish_stage("OLL parity", oll);
+        let pll = if reduced == Err(ValidationError::PermutationParity) {
+            self.apply_algorithm(PLL_PARITY);
+            reduced = reduced_cube(&self.stickers);
+            format!("two edges swapped, fixed with {}", PLL_PARITY)
+        } else {
+            "no PLL parity".to_string()
+        };
+        self.finish_stage("PLL parity", pll);
+        Ok(reduced?)
+    }
+
+    fn apply_algorithm(&mut self, text: &str) {
+        let algorithm: Algorithm = text.parse().unwrap();
+        for cube_move in algorithm.moves.iter() {
+            self.apply_move(*cube_move);
+        }
+    }
+}
+
+impl Turn {
+    fn apply(&self, stickers: &Stickers) -> Stickers {
+        let mut result = *stickers;
+        for (sticker, source) in result.iter_mut().zip(self.sources.iter()) {
+            *sticker = stickers[*source as usize];
+        }
+        result
+    }
+
+    // The turn written with outer and wide moves, an inner layer being the
+    // wide move less the outer one.
+    fn moves(&self) -> Vec<Move> {
+        match self.depth {
+            0 => vec![Move::Face(self.face, self.direction)],
+            _ => vec![
+                Move::Wide(self.face, self.direction),
+                Move::Face(self.face, self.direction.opposite()),
+            ],
+        }
+    }
+}
+
+impl CenterStage {
+    fn new(groups: Vec<Vec<ColorFacet>>, turns: Vec<usize>, goals: &[&str]) -> CenterStage {
+        let mut stage = CenterStage {
+            groups,
+            turns,
+            depths: HashMap::new(),
+        };
+        let mut layer: Vec<u128> = goals
+            .iter()
+            .map(|goal| {
+                let cube = Cube::<4>::new().apply(&goal.parse().unwrap());
+                stage.key(&stickers(&cube))
+            })
+            .collect();
+        for key in layer.iter() {
+            stage.depths.insert(*key, 0);
+        }
+        let moves = center_moves();
+        let mut depth = 0;
+        while !layer.is_empty() {
+            depth += 1;
+            let mut next_layer = Vec::new();
+            for key in layer {
+                for turn in stage.turns.iter() {
+                    let next = turn_key(key, &moves[*turn], stage.groups.len());
+                    stage.depths.entry(next).or_insert_with(|| {
+                        next_layer.push(next);
+                        depth
+                    });
+                }
+            }
+            layer = next_layer;
+        }
+        stage
+    }
+
+    fn depth(&self, stickers: &Stickers) -> Option<u8> {
+        self.depths.get(&self.key(stickers)).cloned()
+    }
+
+    // For each group of colors, which centers have one of them.
+    fn key(&self, stickers: &Stickers) -> u128 {
+        let centers = center_stickers();
+        self.groups.iter().enumerate().fold(0, |key, (i, group)| {
+            let mask = centers
+                .iter()
+                .enumerate()
+                .filter(|(_, s)| group.contains(&stickers[**s]))
+                .fold(0u128, |mask, (c, _)| mask | 1 << c);
+            key | mask << (24 * i)
+        })
+    }
+}
+
+fn turn_key(key: u128, destinations: &[usize; 24], groups: usize) -> u128 {
+    let mut next = 0;
+    for group in 0..groups {
+        for (center, destination) in destinations.iter().enumerate() {
+            if key & 1 << (24 * group + center) != 0 {
+                next |= 1 << (24 * group + destination);
+            }
+        }
+    }
+    next
+}
+
+fn stickers(cube: &Cube<4>) -> Stickers {
+    let mut stickers = [ColorFacet::Red; STICKERS];
+    for (i, face) in cube.faces.iter().enumerate() {
+        for (j, facet) in face.facets.iter().flatten().enumerate() {
+            stickers[i * 16 + j] = facet.color;
+        }
+    }
+    stickers
+}
+
+fn face_of(sticker: usize) -> ColorFacet {
+    *ColorFacet::iterator().nth(sticker / 16).unwrap()
+}
+
+fn sticker_position(sticker: usize) -> [i32; 3] {
+    position(face_of(sticker), sticker % 16 / 4, sticker % 4, 4)
+}
+
+// The 36 layer turns, numbered ((face * 2) + depth) * 3 + direction with
+// faces in the order of `ColorFacet::iterator`.
+fn turns() -> &'static Vec<Turn> {
+    static TURNS: OnceLock<Vec<Turn>> = OnceLock::new();
+    TURNS.get_or_init(|| {
+        let mut turns = Vec::new();
+        for face in ColorFacet::iterator() {
+            for depth in 0..2 {
+                for direction in DIRECTIONS.iter() {
+                    let cube = Cube::<4>::new().turn(*face, depth, *direction);
+                    let mut sources = [0; STICKERS];
+                    for (i, grid) in cube.faces.iter().enumerate() {
+                        for (j, facet) in grid.facets.iter().flatten().enumerate() {
+                            let home = face_index(facet.color);
+                            sources[i * 16 + j] = (home * 16 + facet.index as usize - 1) as u8;
+                        }
+                    }
+                    turns.push(Turn {
+                        face: *face,
+                        depth,
+                        direction: *direction,
+                        sources,
+                    });
+                }
+            }
+        }
+        turns
+    })
+}
+
+fn turn_index(face: ColorFacet, depth: usize, direction: RotationDirection) -> usize {
+    let face = face_index(face);
+    let direction = DIRECTIONS.iter().position(|d| *d == direction).unwrap();
+    (face * 2 + depth) * 3 + direction
+}
+
+// Wide moves turn the two outer layers, as `Cube` does.
+fn move_turns(cube_move: Move) -> Vec<usize> {
+    match cube_move {
+        Move::Face(face, direction) => vec![turn_index(face, 0, direction)],
+        Move::Wide(face, direction) => vec![
+            turn_index(face, 0, direction),
+            turn_index(face, 1, direction),
+        ],
+        Move::Rotation(axis, direction) => {
+            let face = axis.face();
+            (0..2)
+                .flat_map(|depth| {
+                    vec![
+                        turn_index(face, depth, direction),
+                        turn_index(face.opposite(), depth, direction.opposite()),
+                    ]
+                })
+                .collect()
+        }
+        Move::Slice(slice, direction) => {
+            let face = slice.face();
+            vec![
+                turn_index(face, 1, direction),
+                turn_index(face.opposite(), 1, direction.opposite()),
+            ]
+        }
+    }
+}
+
+// The stickers in the middle of each face.
+fn center_stickers() -> Vec<usize> {
+    (0..STICKERS)
+        .filter(|s| {
+            let (row, column) = (s % 16 / 4, s % 4);
+            (1..3).contains(&row) && (1..3).contains(&column)
+        })
+        .collect()
+}
+
+// Where each turn takes each center, numbered as in `center_stickers`.
+fn center_moves() -> Vec<[usize; 24]> {
+    let centers = center_stickers();
+    turns()
+        .iter()
+        .map(|turn| {
+            let mut destinations = [0; 24];
+            for (to, sticker) in centers.iter().enumerate() {
+                let from = turn.sources[*sticker] as usize;
+                destinations[centers.iter().position(|c| *c == from).unwrap()] = to;
+            }
+            destinations
+        })
+        .collect()
+}
+
+fn center_stages() -> &'static Vec<CenterStage> {
+    static STAGES: OnceLock<Vec<CenterStage>> = OnceLock::new();
+    STAGES.get_or_init(|| {
+        let all: Vec<usize> = (0..turns().len()).collect();
+        // Outer turns only turn the centers of their face, and the inner
+        // layers keep up and down centers on the up and down faces with any
+        // turn of the up and down ones and half turns of the others.
+        let keep_up_down: Vec<usize> = (0..turns().len())
+            .filter(|t| {
+                let turn = &turns()[*t];
+                turn.depth == 0
+                    || UP_DOWN.contains(&turn.face)
+                    || turn.direction == RotationDirection::HalfTurn
+            })
+            .collect();
+        // Half turns of the inner layers also keep the side centers on their
+        // axis.
+        let keep_axes: Vec<usize> = keep_up_down
+            .iter()
+            .cloned()
+            .filter(|t| {
+                let turn = &turns()[*t];
+                turn.depth == 0 || turn.direction == RotationDirection::HalfTurn
+            })
+            .collect();
+        vec![
+            CenterStage::new(vec![UP_DOWN.to_vec()], all, &[""]),
+            CenterStage::new(
+                vec![vec![ColorFacet::Blue, ColorFacet::Green]],
+                keep_up_down,
+                &[""],
+            ),
+            CenterStage::new(
+                vec![
+                    vec![ColorFacet::White],
+                    vec![ColorFacet::Blue],
+                    vec![ColorFacet::Red],
+                ],
+                keep_axes,
+                &["", "x2", "y2", "z2"],
+            ),
+        ]
+    })
+}
+
+fn centers_solved(stickers: &Stickers) -> bool {
+    let centers = center_stickers();
+    centers
+        .chunks(4)
+        .all(|face| face.iter().all(|s| stickers[*s] == stickers[face[0]]))
+}
+
+// Pairs of stickers of each wing, grouped by the edge they belong to.
+fn edges() -> &'static Vec<[[usize; 2]; 2]> {
+    static EDGES: OnceLock<Vec<[[usize; 2]; 2]>> = OnceLock::new();
+    EDGES.get_or_init(|| {
+        let mut wings: Vec<[usize; 2]> = Vec::new();
+        for a in 0..STICKERS {
+            for b in a + 1..STICKERS {
+                let same = sticker_position(a) == sticker_position(b);
+                let corner = (0..STICKERS)
+                    .filter(|s| sticker_position(*s) == sticker_position(a))
+                    .count()
+                    == 3;
+                if same && !corner {
+                    wings.push([a, b]);
+                }
+            }
+        }
+        let faces = |wing: &[usize; 2]| (wing[0] / 16, wing[1] / 16);
+        let mut edges: Vec<[[usize; 2]; 2]> = Vec::new();
+        for wing in wings.iter() {
+            if !edges.iter().any(|e| e.contains(wing)) {
+                let other = wings
+                    .iter()
+                    .find(|w| *w != wing && faces(w) == faces(wing))
+                    .unwrap();
+                edges.push([*wing, *other]);
+            }
+        }
+        edges
+    })
+}
+
+fn paired_edges(stickers: &Stickers) -> usize {
+    edges()
+        .iter()
+        .filter(|[first, second]| {
+            stickers[first[0]] == stickers[second[0]] && stickers[first[1]] == stickers[second[1]]
+        })
+        .count()
+}
+
+// Every sequence of outer turns of the given length not turning the same
+// face twice in a row.
+fn setups(outer: &[usize], length: usize) -> Vec<Vec<usize>> {
+    (0..length).fold(vec![Vec::new()], |setups, _| {
+        setups
+            .into_iter()
+            .flat_map(|setup: Vec<usize>| {
+                outer
+                    .iter()
+                    .filter(|t| setup.last().map(|l| l / 6 != *t / 6).unwrap_or(true))
+                    .map(|t| {
+                        let mut next = setup.clone();
+                        next.push(*t);
+                        next
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    })
+}
+
+// The 3x3 a reduced 4x4 stands for: its corners, one wing of each edge and
+// one sticker of each center.
+fn reduced_cube(stickers: &Stickers) -> Result<RubiksCube, ValidationError> {
+    let scheme = ColorScheme::default();
+    let facelets: String = scheme
+        .colors()
+        .iter()
+        .flat_map(|face| {
+            let face = face_index(*face);
+            LINES.iter().flat_map(move |row| {
+                LINES
+                    .iter()
+                    .map(move |column| stickers[face * 16 + row * 4 + column])
+            })
+        })
+        .map(|color| scheme.letter(color).unwrap())
+        .collect();
+    RubiksCube::from_facelets(&facelets).map_err(|error| match error {
+        FaceletError::Unsolvable(error) => error,
+        _ => ValidationError::InvalidCenters,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solve_should_reduce_and_solve_scrambles() {
+        let solver = ReductionSolver::new();
+        let scrambles = [
+            "R U R' U'",
+            "Rw U2 Fw' R2 Uw' B L2 Dw F' Rw2 U' Lw B2 Uw2 R' Fw D Bw' L U",
+            "Uw2 Fw R' Dw L2 Bw2 U' Rw F2 Lw' D Fw2 B' Uw R2 Dw2 L Bw' F Rw'",
+            "x Rw' Uw R2 Fw2 D' Lw2 Bw U Rw2 F' Dw B2 L' Uw' Fw R D2 Bw2 y",
+        ];
+        for scramble in scrambles.iter() {
+            let cube = Cube::<4>::new().apply(&scramble.parse().unwrap());
+            let solution = solver.solve(&cube).unwrap();
+            assert!(
+                cube.apply(&solution.algorithm()).is_solved(),
+                "{}",
+                scramble
+            );
+            let names: Vec<&str> = solution.stages.iter().map(|s| s.name.as_str()).collect();
+            assert_eq!(
+                names,
+                vec!["centers", "edge pairing", "OLL parity", "PLL parity", "3x3"]
+            );
+        }
+    }
+
+    #[test]
+    fn test_solve_should_fix_parities() {
+        let solver = ReductionSolver::new();
+        let flipped = Cube::<4>::new().apply(&OLL_PARITY.parse().unwrap());
+        let solution = solver.solve(&flipped).unwrap();
+        assert!(flipped.apply(&solution.algorithm()).is_solved());
+        assert!(!solution.stage("OLL parity").unwrap().moves.is_empty());
+        let swapped = Cube::<4>::new().apply(&PLL_PARITY.parse().unwrap());
+        let solution = solver.solve(&swapped).unwrap();
+        assert!(swapped.apply(&solution.algorithm()).is_solved());
+        assert!(solution.stage("OLL parity").unwrap().moves.is_empty());
+        assert_eq!(
+            solution.stage("PLL parity").unwrap().description,
+            format!("two edges swapped, fixed with {}", PLL_PARITY)
+        );
+        let solution = solver.solve(&Cube::new()).unwrap();
+        assert!(solution.is_empty());
+    }
+}
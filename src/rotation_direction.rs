@@ -4,13 +4,15 @@ use std::slice::Iter;
 pub enum RotationDirection {
     Clockwise,
     Anticlockwise,
+    HalfTurn,
 }
 
 impl RotationDirection {
     pub fn iterator() -> Iter<'static, RotationDirection> {
-        static ROTATION_DIRECTION: [RotationDirection; 2] = [
+        static ROTATION_DIRECTION: [RotationDirection; 3] = [
             RotationDirection::Clockwise,
             RotationDirection::Anticlockwise,
+            RotationDirection::HalfTurn,
         ];
         ROTATION_DIRECTION.iter()
     }
@@ -19,6 +21,26 @@ impl RotationDirection {
         match self {
             RotationDirection::Clockwise => RotationDirection::Anticlockwise,
             RotationDirection::Anticlockwise => RotationDirection::Clockwise,
+            RotationDirection::HalfTurn => RotationDirection::HalfTurn,
+        }
+    }
+
+    // Clockwise quarter turns making up the turn.
+    pub fn quarter_turns(self) -> usize {
+        match self {
+            RotationDirection::Clockwise => 1,
+            RotationDirection::Anticlockwise => 3,
+            RotationDirection::HalfTurn => 2,
+        }
+    }
+
+    // None when the quarter turns add up to a whole turn.
+    pub fn from_quarter_turns(turns: usize) -> Option<RotationDirection> {
+        match turns % 4 {
+            1 => Some(RotationDirection::Clockwise),
+            2 => Some(RotationDirection::HalfTurn),
+            3 => Some(RotationDirection::Anticlockwise),
+            _ => None,
         }
     }
 }
@@ -0,0 +1,373 @@
+use crate::pattern_databases::BlockTable;
+use crate::solving_cube::{corner_solved, edge_solved, SolvingCube, UP_TURNS};
+use crate::Algorithm;
+use crate::ColorFacet;
+use crate::CubieCube;
+use crate::Move;
+use crate::RubiksCube;
+use crate::Slice;
+use crate::Solution;
+use crate::SolveError;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+const FIRST_BLOCK_MOVES: [&str; 24] = [
+    "U", "U2", "U'", "R", "R2", "R'", "F", "F2", "F'", "D", "D2", "D'", "L", "L2", "L'", "B", "B2",
+    "B'", "r", "r2", "r'", "M", "M2", "M'",
+];
+const SECOND_BLOCK_MOVES: [&str; 12] = [
+    "U", "U2", "U'", "R", "R2", "R'", "r", "r2", "r'", "M", "M2", "M'",
+];
+const LAST_SIX_EDGES_MOVES: [&str; 6] = ["U", "U2", "U'", "M", "M2", "M'"];
+
+// Pieces of the left and right blocks, numbered as in `CubieCube`.
+const FIRST_BLOCK_CORNERS: [usize; 2] = [5, 6];
+const FIRST_BLOCK_EDGES: [usize; 3] = [6, 9, 10];
+const SECOND_BLOCK_CORNERS: [usize; 2] = [4, 7];
+const SECOND_BLOCK_EDGES: [usize; 3] = [4, 8, 11];
+
+// The edges and centers left once both blocks are built.
+const LAST_SIX_EDGES: [usize; 6] = [0, 1, 2, 3, 5, 7];
+
+const LEFT: usize = 4;
+const RIGHT: usize = 1;
+const DOWN: usize = 3;
+
+pub(crate) const CMLL_CASES: [(&str, &str); 42] = [
+    ("O adjacent", "R U R' F' R U R' U' R' F R2 U' R'"),
+    ("O diagonal", "F R U' R' U' R U R' F' R U R' U' R' F R F'"),
+    ("H columns", "R U2 R' U' R U R' U' R U' R'"),
+    ("H rows", "F R U R' U' R U R' U' R U R' U' F'"),
+    ("H column", "R U2 R2 F R F' U2 R' F R F'"),
+    ("H row", "r U' r2 D' r U' r' D r2 U r'"),
+    ("Pi right bar", "F R U R' U' R U R' U' F'"),
+    ("Pi back slash", "F R' F' R U2 R U' R' U R U2 R'"),
+    ("Pi X checkerboard", "R' F R U F U' R U R' U' F'"),
+    ("Pi forward slash", "R U2 R' U' R U R' U2 R' F R F'"),
+    ("Pi columns", "r U' r2 D' r U r' D r2 U r'"),
+    ("Pi left bar", "R' U' R' F R F' R U' R' U2 R"),
+    ("U forward slash", "R2 D R' U2 R D' R' U2 R'"),
+    ("U back slash", "R2 D' R U2 R' D R U2 R"),
+    ("U front row", "R2 F U' F U F2 R2 U' R' F R"),
+    ("U rows", "F R2 D R' U R D' R2 U' F'"),
+    ("U X checkerboard", "r U' r' U r' D' r U' r' D r"),
+    ("U back row", "F R U R' U' F'"),
+    ("T left bar", "R U R' U' R' F R F'"),
+    ("T right bar", "L' U' L U L F' L' F"),
+    ("T rows", "F R' F R2 U' R' U' R U R' F2"),
+    ("T front row", "r' U r U2 R2 F R F' R"),
+    ("T back row", "r' D' r U r' D r U' r U r'"),
+    ("T columns", "r2 D' r U r' D r2 U' r' U' r"),
+    ("S left bar", "R U R' U R U2 R'"),
+    ("S X checkerboard", "L' U2 L U2 L F' L' F"),
+    ("S forward slash", "F R' F' R U2 R U2 R'"),
+    ("S columns", "R U R' U' R' F R F' R U R' U R U2 R'"),
+    ("S right bar", "R U R' F' R U R' U R U2 R' F R U' R'"),
+    ("S back slash", "R U' L' U R' U' L"),
+    ("As right bar", "R' U' R U' R' U2 R"),
+    ("As columns", "R2 D R' U R D' R' U R' U' R U' R'"),
+    ("As back slash", "F' L F L' U2 L' U2 L"),
+    ("As X checkerboard", "R U2 R' U2 R' F R F'"),
+    ("As forward slash", "L' U R U' L U R'"),
+    ("As left bar", "L' U' L F L' U' L U' L' U2 L F' L' U L"),
+    ("L mirror", "F R U' R' U' R U R' F'"),
+    ("L inverse", "F R' F' R U R U' R'"),
+    ("L pure", "R U2 R' U' R U R' U' R U R' U' R U' R'"),
+    ("L front commutator", "R U2 R D R' U2 R D' R2"),
+    ("L diag", "R' U' R U R' F' R U R' U' R' F R2"),
+    ("L back commutator", "R' U2 R' D' R U2 R' D R2"),
+];
+
+#[derive(PartialEq, Debug, Clone, Copy, Default)]
+pub struct RouxSolver {}
+
+impl RouxSolver {
+    pub fn new() -> RouxSolver {
+        RouxSolver {}
+    }
+
+    // Builds a block on the left and one on the right, solves the corners
+    // of the up layer, then the last six edges with the middle slice.
+    pub fn solve(&self, cube: &RubiksCube) -> Result<Solution, SolveError> {
+        let mut solving = SolvingCube::new(cube)?;
+        solve_first_block(&mut solving);
+        solve_second_block(&mut solving);
+        let (name, algorithm) = cmll_case(&solving.cube).unwrap();
+        solving.apply(&algorithm);
+        solving.finish_stage("CMLL", name.to_string());
+        solve_last_six_edges(&mut solving);
+        Ok(solving.into_solution())
+    }
+
+    // The name of the corner case of the up layer, like "Pi right bar" or
+    // "CMLL skip", if both blocks are built.
+    pub fn recognize_cmll(&self, cube: &RubiksCube) -> Option<&'static str> {
+        let solving = SolvingCube::new(cube).ok()?;
+        cmll_case(&solving.cube).map(|(name, _)| name)
+    }
+}
+
+// The cube as it is in space, with the pieces named after the centers of
+// the middle slice turned back in line with the left block, if it is built.
+// Slice turns leave the blocks where they are, while the cube is described
+// from its centers.
+fn in_place(cube: &CubieCube) -> Option<CubieCube> {
+    let rotation = turn_in_place(CubieCube::new(), "R M' L'");
+    (0..4)
+        .scan(*cube, |turned, _| {
+            let current = *turned;
+            *turned = rotation.multiply(*turned);
+            Some(current)
+        })
+        .find(|turned| block_solved(turned, &FIRST_BLOCK_CORNERS, &FIRST_BLOCK_EDGES))
+}
+
+fn block_solved(cube: &CubieCube, corners: &[usize], edges: &[usize]) -> bool {
+    corners.iter().all(|c| corner_solved(cube, *c)) && edges.iter().all(|e| edge_solved(cube, *e))
+}
+
+fn blocks_solved(cube: &CubieCube) -> bool {
+    in_place(cube)
+        .is_some_and(|cube| block_solved(&cube, &SECOND_BLOCK_CORNERS, &SECOND_BLOCK_EDGES))
+}
+
+fn corners_solved(cube: &CubieCube) -> bool {
+    in_place(cube).is_some_and(|cube| {
+        UP_TURNS.iter().any(|turn| {
+            let turned = cube.apply(&turn.parse().unwrap());
+            (0..4).all(|c| corner_solved(&turned, c))
+        })
+    })
+}
+
+// The moves turning the cubies as they are in space, with `r` taking the
+// middle slice along with the right face.
+fn turn_in_place(cube: CubieCube, algorithm: &str) -> CubieCube {
+    let algorithm: Algorithm = algorithm.parse().unwrap();
+    algorithm
+        .moves
+        .iter()
+        .fold(cube, |cube, cube_move| match *cube_move {
+            Move::Slice(slice, direction) => cube.rotate_slice_in_place(slice, direction),
+            Move::Wide(ColorFacet::Blue, direction) => cube
+                .rotate(ColorFacet::Blue, direction)
+                .rotate_slice_in_place(Slice::Middle, direction.opposite()),
+            _ => cube.apply_move(*cube_move),
+        })
+}
+
+fn block_table(corners: &[usize], edges: &[usize], moves: &[&str]) -> BlockTable {
+    let moves = moves
+        .iter()
+        .map(|m| turn_in_place(CubieCube::new(), m))
+        .collect();
+    BlockTable::new(corners.to_vec(), edges.to_vec(), moves)
+}
+
+fn first_block_table() -> &'static BlockTable {
+    static TABLE: OnceLock<BlockTable> = OnceLock::new();
+    TABLE.get_or_init(|| block_table(&FIRST_BLOCK_CORNERS, &FIRST_BLOCK_EDGES, &FIRST_BLOCK_MOVES))
+}
+
+fn second_block_table() -> &'static BlockTable {
+    static TABLE: OnceLock<BlockTable> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        block_table(
+            &SECOND_BLOCK_CORNERS,
+            &SECOND_BLOCK_EDGES,
+            &SECOND_BLOCK_MOVES,
+        )
+    })
+}
+
+// Distances of every arrangement of the last six edges and the middle
+// centers, over M and U, found by a breadth first search from solved.
+fn last_six_edges_table() -> &'static HashMap<u128, u8> {
+    static TABLE: OnceLock<HashMap<u128, u8>> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        let moves: Vec<Algorithm> = LAST_SIX_EDGES_MOVES
+            .iter()
+            .map(|m| m.parse().unwrap())
+            .collect();
+        let mut depths = HashMap::new();
+        depths.insert(key(&CubieCube::new()), 0);
+        let mut layer = vec![CubieCube::new()];
+        let mut depth = 0;
+        while !layer.is_empty() {
+            depth += 1;
+            let mut next_layer = Vec::new();
+            for cube in layer {
+                for algorithm in moves.iter() {
+                    let next = cube.apply(algorithm);
+                    depths.entry(key(&next)).or_insert_with(|| {
+                        next_layer.push(next);
+                        depth
+                    });
+                }
+            }
+            layer = next_layer;
+        }
+        depths
+    })
+}
+
+fn key(cube: &CubieCube) -> u128 {
+    let corners = cube
+        .corner_permutation
+        .iter()
+        .zip(cube.corner_orientation.iter())
+        .map(|(corner, orientation)| *corner as u128 * 3 + *orientation as u128);
+    let edges = cube
+        .edge_permutation
+        .iter()
+        .zip(cube.edge_orientation.iter())
+        .map(|(edge, orientation)| *edge as u128 * 2 + *orientation as u128);
+    corners.chain(edges).fold(0, |key, piece| key * 32 + piece)
+}
+
+fn placed(cube: &CubieCube, corners: &[usize], edges: &[usize]) -> usize {
+    corners.iter().filter(|c| corner_solved(cube, **c)).count()
+        + edges.iter().filter(|e| edge_solved(cube, **e)).count()
+}
+
+fn solve_first_block(solving: &mut SolvingCube) {
+    let table = first_block_table();
+    let count = placed(&solving.cube, &FIRST_BLOCK_CORNERS, &FIRST_BLOCK_EDGES);
+    let moves: Vec<&str> = table
+        .solve(&solving.cube)
+        .into_iter()
+        .map(|m| FIRST_BLOCK_MOVES[m])
+        .collect();
+    solving.apply(&moves.join(" "));
+    let description = format!(
+        "{}-{} block on the left, {} of 5 pieces were already in place",
+        solving.color(LEFT).name(),
+        solving.color(DOWN).name(),
+        count
+    );
+    solving.finish_stage("first block", description);
+}
+
+fn solve_second_block(solving: &mut SolvingCube) {
+    let table = second_block_table();
+    let cube = in_place(&solving.cube).unwrap();
+    let count = placed(&cube, &SECOND_BLOCK_CORNERS, &SECOND_BLOCK_EDGES);
+    let moves: Vec<&str> = table
+        .solve(&cube)
+        .into_iter()
+        .map(|m| SECOND_BLOCK_MOVES[m])
+        .collect();
+    solving.apply(&moves.join(" "));
+    let description = format!(
+        "{} block on the right, {} of 5 pieces were already in place",
+        solving.color(RIGHT).name(),
+        count
+    );
+    solving.finish_stage("second block", description);
+}
+
+// The case and the algorithm solving it, preceded by the turn of the up face
+// that puts the case in front.
+fn cmll_case(cube: &CubieCube) -> Option<(&'static str, String)> {
+    if !blocks_solved(cube) {
+        return None;
+    }
+    if corners_solved(cube) {
+        return Some(("CMLL skip", String::new()));
+    }
+    CMLL_CASES.iter().find_map(|(name, algorithm)| {
+        UP_TURNS
+            .iter()
+            .map(|turn| format!("{} {}", turn, algorithm))
+            .find(|candidate| corners_solved(&cube.apply(&candidate.parse().unwrap())))
+            .map(|candidate| (*name, candidate))
+    })
+}
+
+fn solve_last_six_edges(solving: &mut SolvingCube) {
+    let table = last_six_edges_table();
+    let cube = in_place(&solving.cube).unwrap();
+    let count = LAST_SIX_EDGES
+        .iter()
+        .filter(|e| edge_solved(&cube, **e))
+        .count();
+    let moves: Vec<Algorithm> = LAST_SIX_EDGES_MOVES
+        .iter()
+        .map(|m| m.parse().unwrap())
+        .collect();
+    while table[&key(&solving.cube)] > 0 {
+        let depth = table[&key(&solving.cube)];
+        let next = moves
+            .iter()
+            .find(|m| table[&key(&solving.cube.apply(m))] < depth)
+            .unwrap();
+        solving.apply_algorithm(next);
+    }
+    let description = format!("{} of 6 edges were already in place", count);
+    solving.finish_stage("LSE", description);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_in_place_should_follow_slice_turns() {
+        for algorithm in ["M", "M2 U r'", "R U M' r2 U'"].iter() {
+            let cube = CubieCube::new().apply(&algorithm.parse().unwrap());
+            assert_eq!(
+                in_place(&cube),
+                Some(turn_in_place(CubieCube::new(), algorithm))
+            );
+        }
+    }
+
+    #[test]
+    fn test_recognize_cmll_should_name_every_case() {
+        let solver = RouxSolver::new();
+        for (name, algorithm) in CMLL_CASES.iter() {
+            for turn in UP_TURNS.iter() {
+                let cube = RubiksCube::new()
+                    .apply(&algorithm.parse::<Algorithm>().unwrap().inverse())
+                    .apply(&turn.parse().unwrap());
+                assert_eq!(solver.recognize_cmll(&cube), Some(*name), "{}", name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_solve_should_solve_scrambles_in_named_stages() {
+        let solver = RouxSolver::new();
+        let scrambles = [
+            "R U R' U'",
+            "F2 D' L B2 R' U2 F' D2 L2 B R2 U' F L' D B' R U2 L F'",
+            "U R2 F B R B2 R U2 L B2 R U' D' R2 F R' L B2 U2 F2",
+            "M' E S2 r U2 x y' F' D",
+            "L2 D B' U R2 F' L' D2 B U' R' F2 D L B2 U2 R D' F L'",
+        ];
+        for scramble in scrambles.iter() {
+            let cube = RubiksCube::new().apply(&scramble.parse().unwrap());
+            let solution = solver.solve(&cube).unwrap();
+            assert!(cube.apply(&solution.algorithm()).is_solved());
+            let names: Vec<&str> = solution.stages.iter().map(|s| s.name.as_str()).collect();
+            assert_eq!(names, vec!["first block", "second block", "CMLL", "LSE"]);
+        }
+    }
+
+    #[test]
+    fn test_solve_should_use_slices_for_the_last_six_edges() {
+        let solver = RouxSolver::new();
+        let cube = RubiksCube::new().apply(&"M' U2 M U".parse().unwrap());
+        let solution = solver.solve(&cube).unwrap();
+        assert!(solution.stage("first block").unwrap().moves.is_empty());
+        assert!(solution.stage("second block").unwrap().moves.is_empty());
+        assert_eq!(solution.stage("CMLL").unwrap().description, "CMLL skip");
+        assert_eq!(
+            solution.stage("LSE").unwrap().moves.to_string(),
+            "U' M' U2 M"
+        );
+        assert_eq!(solver.recognize_cmll(&RubiksCube::new()), Some("CMLL skip"));
+        let cube = RubiksCube::new().apply(&"R U R'".parse().unwrap());
+        assert_eq!(solver.recognize_cmll(&cube), None);
+    }
+}
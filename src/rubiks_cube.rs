@@ -1,6 +1,16 @@
+use crate::cubie_cube::FACES;
+use crate::Algorithm;
+use crate::Axis;
 use crate::ColorFacet;
+use crate::ColorScheme;
+use crate::CubieCube;
 use crate::Face;
+use crate::FaceletError;
+use crate::Facet;
+use crate::Move;
 use crate::RotationDirection;
+use crate::Slice;
+use crate::ValidationError;
 
 #[derive(PartialEq, Clone, Copy)]
 pub struct RubiksCube {
@@ -22,6 +32,25 @@ impl std::fmt::Debug for RubiksCube {
     }
 }
 
+// Composes the states as permutations of the facets: the result is what
+// doing the moves of `self` and then those of `other` does to a solved cube.
+// Both cubes must hold every facet of a solved cube once, as turns leave
+// them; cubes with edited colors or indexes give a meaningless result.
+impl std::ops::Mul for RubiksCube {
+    type Output = RubiksCube;
+
+    fn mul(self, other: RubiksCube) -> RubiksCube {
+        let facets = self.facet_list();
+        RubiksCube::from_facet_list(
+            other
+                .facet_list()
+                .iter()
+                .map(|facet| facets[home_position(facet)])
+                .collect(),
+        )
+    }
+}
+
 impl Default for RubiksCube {
     fn default() -> Self {
         Self::new()
@@ -40,6 +69,181 @@ impl RubiksCube {
         }
     }
 
+    // The solved cube, which leaves a state unchanged when composed with it.
+    pub fn identity() -> RubiksCube {
+        RubiksCube::new()
+    }
+
+    // The state that composed with this one gives the solved cube. Like
+    // `*`, it needs every facet of a solved cube to be on the cube once.
+    pub fn inverse(&self) -> RubiksCube {
+        let solved = RubiksCube::new().facet_list();
+        let mut facets = solved.clone();
+        for (position, facet) in self.facet_list().iter().enumerate() {
+            facets[home_position(facet)] = solved[position];
+        }
+        RubiksCube::from_facet_list(facets)
+    }
+
+    // Whether both cubes show the same colors everywhere, even if facets of
+    // the same color are swapped. `==` also compares their indexes.
+    pub fn same_colors(&self, other: &RubiksCube) -> bool {
+        self.facet_list()
+            .iter()
+            .zip(other.facet_list().iter())
+            .all(|(facet, other)| facet.color == other.color)
+    }
+
+    // Facets in the order of `to_facelets`.
+    fn facet_list(&self) -> Vec<Facet> {
+        FACES
+            .iter()
+            .flat_map(|face| self.face(*face).facets().to_vec())
+            .collect()
+    }
+
+    fn from_facet_list(facets: Vec<Facet>) -> RubiksCube {
+        FACES
+            .iter()
+            .enumerate()
+            .fold(RubiksCube::new(), |cube, (i, face)| {
+                let mut content = [facets[i * 9]; 9];
+                content.copy_from_slice(&facets[i * 9..i * 9 + 9]);
+                cube.with_face(*face, Face::from_facets(content))
+            })
+    }
+
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        for color in ColorFacet::iterator() {
+            let count = ColorFacet::iterator()
+                .flat_map(|face| self.face(*face).facets().to_vec())
+                .filter(|facet| facet.color == *color)
+                .count();
+            if count != 9 {
+                return Err(ValidationError::WrongColorCount(*color, count));
+            }
+        }
+        CubieCube::from_rubiks_cube(self)?.validate()
+    }
+
+    pub fn from_facelets(facelets: &str) -> Result<RubiksCube, FaceletError> {
+        RubiksCube::from_facelets_with_scheme(facelets, &ColorScheme::default())
+    }
+
+    // Reads a 54 letter string listing the U, R, F, D, L and B faces as laid
+    // out in the net, where each letter names the face whose color is there.
+    pub fn from_facelets_with_scheme(
+        facelets: &str,
+        scheme: &ColorScheme,
+    ) -> Result<RubiksCube, FaceletError> {
+        if !scheme.is_valid() {
+            return Err(FaceletError::InvalidScheme);
+        }
+        let letters: Vec<char> = facelets.chars().collect();
+        if letters.len() != 54 {
+            return Err(FaceletError::WrongLength(letters.len()));
+        }
+        let mut cube = RubiksCube::new();
+        for (i, face) in FACES.iter().enumerate() {
+            let mut facets = cube.face(*face).facets();
+            for (position, facet) in facets.iter_mut().enumerate() {
+                let letter = letters[i * 9 + position];
+                facet.color = scheme
+                    .color(letter)
+                    .ok_or(FaceletError::UnknownLetter(i * 9 + position, letter))?;
+            }
+            cube = cube.with_face(*face, Face::from_facets(facets));
+        }
+        cube.validate()?;
+        let orientation = cube.orientation().ok_or(ValidationError::InvalidCenters)?;
+        Ok(CubieCube::from_rubiks_cube(&cube)?.to_rubiks_cube_oriented(&orientation))
+    }
+
+    pub fn to_facelets(&self) -> String {
+        self.to_facelets_with_scheme(&ColorScheme::default())
+            .unwrap()
+    }
+
+    pub fn to_facelets_with_scheme(&self, scheme: &ColorScheme) -> Result<String, FaceletError> {
+        if !scheme.is_valid() {
+            return Err(FaceletError::InvalidScheme);
+        }
+        Ok(FACES
+            .iter()
+            .flat_map(|face| self.face(*face).facets().to_vec())
+            .map(|facet| scheme.letter(facet.color).unwrap())
+            .collect())
+    }
+
+    // The 24 ways to hold the cube, each given as the rotations that take
+    // the home orientation there.
+    pub fn orientations() -> Vec<Algorithm> {
+        let ups = ["", "x", "x2", "x'", "z", "z'"];
+        let fronts = ["", "y", "y2", "y'"];
+        ups.iter()
+            .flat_map(|up| {
+                fronts
+                    .iter()
+                    .map(move |front| format!("{} {}", up, front).parse().unwrap())
+            })
+            .collect()
+    }
+
+    pub fn orientation(&self) -> Option<Algorithm> {
+        RubiksCube::orientations().into_iter().find(|rotations| {
+            let held = RubiksCube::new().apply(rotations);
+            ColorFacet::iterator()
+                .all(|face| held.face(*face).center.color == self.face(*face).center.color)
+        })
+    }
+
+    pub fn is_solved(&self) -> bool {
+        ColorFacet::iterator().all(|face| {
+            let facets = self.face(*face).facets();
+            facets.iter().all(|facet| facet.color == facets[4].color)
+        })
+    }
+
+    pub fn face(&self, face: ColorFacet) -> Face {
+        match face {
+            ColorFacet::Blue => self.blue,
+            ColorFacet::Red => self.red,
+            ColorFacet::Green => self.green,
+            ColorFacet::Orange => self.orange,
+            ColorFacet::White => self.white,
+            ColorFacet::Yellow => self.yellow,
+        }
+    }
+
+    pub fn with_face(self, face: ColorFacet, content: Face) -> RubiksCube {
+        match face {
+            ColorFacet::Blue => RubiksCube {
+                blue: content,
+                ..self
+            },
+            ColorFacet::Red => RubiksCube {
+                red: content,
+                ..self
+            },
+            ColorFacet::Green => RubiksCube {
+                green: content,
+                ..self
+            },
+            ColorFacet::Orange => RubiksCube {
+                orange: content,
+                ..self
+            },
+            ColorFacet::White => RubiksCube {
+                white: content,
+                ..self
+            },
+            ColorFacet::Yellow => RubiksCube {
+                yellow: content,
+                ..self
+            },
+        }
+    }
+
     pub fn rotate(self, face: ColorFacet, direction: RotationDirection) -> RubiksCube {
         match face {
             ColorFacet::Blue => RubiksCube::rotate_blue(self, direction),
@@ -51,40 +255,143 @@ impl RubiksCube {
         }
     }
 
+    pub fn apply(self, algorithm: &Algorithm) -> RubiksCube {
+        algorithm
+            .moves
+            .iter()
+            .fold(self, |cube, cube_move| cube.apply_move(*cube_move))
+    }
+
+    pub fn apply_move(self, cube_move: Move) -> RubiksCube {
+        match cube_move {
+            Move::Face(face, direction) => self.rotate(face, direction),
+            Move::Slice(slice, direction) => self.rotate_slice(slice, direction),
+            Move::Rotation(axis, direction) => self.rotate_cube(axis, direction),
+            Move::Wide(face, direction) => self.rotate_wide(face, direction),
+        }
+    }
+
+    // A wide move turns a face together with the slice next to it.
+    pub fn rotate_wide(self, face: ColorFacet, direction: RotationDirection) -> RubiksCube {
+        let rotated_cube = self.rotate(face, direction);
+        match face {
+            ColorFacet::Green => rotated_cube.rotate_middle(direction),
+            ColorFacet::Blue => rotated_cube.rotate_middle(direction.opposite()),
+            ColorFacet::Yellow => rotated_cube.rotate_equator(direction),
+            ColorFacet::White => rotated_cube.rotate_equator(direction.opposite()),
+            ColorFacet::Red => rotated_cube.rotate_standing(direction),
+            ColorFacet::Orange => rotated_cube.rotate_standing(direction.opposite()),
+        }
+    }
+
+    pub fn up(&self) -> ColorFacet {
+        self.white.center.color
+    }
+
+    pub fn front(&self) -> ColorFacet {
+        self.red.center.color
+    }
+
+    pub fn rotate_cube(self, axis: Axis, direction: RotationDirection) -> RubiksCube {
+        match axis {
+            Axis::X => RubiksCube::rotate_x(self, direction),
+            Axis::Y => RubiksCube::rotate_y(self, direction),
+            Axis::Z => RubiksCube::rotate_z(self, direction),
+        }
+    }
+
+    pub fn rotate_x(self, direction: RotationDirection) -> RubiksCube {
+        match direction {
+            RotationDirection::Clockwise => RubiksCube::rotate_x_clockwise(self),
+            RotationDirection::Anticlockwise => RubiksCube::rotate_x_anticlockwise(self),
+            RotationDirection::HalfTurn => RubiksCube::rotate_x_half_turn(self),
+        }
+    }
+    pub fn rotate_y(self, direction: RotationDirection) -> RubiksCube {
+        match direction {
+            RotationDirection::Clockwise => RubiksCube::rotate_y_clockwise(self),
+            RotationDirection::Anticlockwise => RubiksCube::rotate_y_anticlockwise(self),
+            RotationDirection::HalfTurn => RubiksCube::rotate_y_half_turn(self),
+        }
+    }
+    pub fn rotate_z(self, direction: RotationDirection) -> RubiksCube {
+        match direction {
+            RotationDirection::Clockwise => RubiksCube::rotate_z_clockwise(self),
+            RotationDirection::Anticlockwise => RubiksCube::rotate_z_anticlockwise(self),
+            RotationDirection::HalfTurn => RubiksCube::rotate_z_half_turn(self),
+        }
+    }
+
+    pub fn rotate_slice(self, slice: Slice, direction: RotationDirection) -> RubiksCube {
+        match slice {
+            Slice::Middle => RubiksCube::rotate_middle(self, direction),
+            Slice::Equator => RubiksCube::rotate_equator(self, direction),
+            Slice::Standing => RubiksCube::rotate_standing(self, direction),
+        }
+    }
+
+    pub fn rotate_middle(self, direction: RotationDirection) -> RubiksCube {
+        match direction {
+            RotationDirection::Clockwise => RubiksCube::rotate_middle_clockwise(self),
+            RotationDirection::Anticlockwise => RubiksCube::rotate_middle_anticlockwise(self),
+            RotationDirection::HalfTurn => RubiksCube::rotate_middle_half_turn(self),
+        }
+    }
+    pub fn rotate_equator(self, direction: RotationDirection) -> RubiksCube {
+        match direction {
+            RotationDirection::Clockwise => RubiksCube::rotate_equator_clockwise(self),
+            RotationDirection::Anticlockwise => RubiksCube::rotate_equator_anticlockwise(self),
+            RotationDirection::HalfTurn => RubiksCube::rotate_equator_half_turn(self),
+        }
+    }
+    pub fn rotate_standing(self, direction: RotationDirection) -> RubiksCube {
+        match direction {
+            RotationDirection::Clockwise => RubiksCube::rotate_standing_clockwise(self),
+            RotationDirection::Anticlockwise => RubiksCube::rotate_standing_anticlockwise(self),
+            RotationDirection::HalfTurn => RubiksCube::rotate_standing_half_turn(self),
+        }
+    }
+
     pub fn rotate_blue(self, direction: RotationDirection) -> RubiksCube {
         match direction {
             RotationDirection::Clockwise => RubiksCube::rotate_blue_clockwise(self),
             RotationDirection::Anticlockwise => RubiksCube::rotate_blue_anticlockwise(self),
+            RotationDirection::HalfTurn => RubiksCube::rotate_blue_half_turn(self),
         }
     }
     pub fn rotate_red(self, direction: RotationDirection) -> RubiksCube {
         match direction {
             RotationDirection::Clockwise => RubiksCube::rotate_red_clockwise(self),
             RotationDirection::Anticlockwise => RubiksCube::rotate_red_anticlockwise(self),
+            RotationDirection::HalfTurn => RubiksCube::rotate_red_half_turn(self),
         }
     }
     pub fn rotate_green(self, direction: RotationDirection) -> RubiksCube {
         match direction {
             RotationDirection::Clockwise => RubiksCube::rotate_green_clockwise(self),
             RotationDirection::Anticlockwise => RubiksCube::rotate_green_anticlockwise(self),
+            RotationDirection::HalfTurn => RubiksCube::rotate_green_half_turn(self),
         }
     }
     pub fn rotate_orange(self, direction: RotationDirection) -> RubiksCube {
         match direction {
             RotationDirection::Clockwise => RubiksCube::rotate_orange_clockwise(self),
             RotationDirection::Anticlockwise => RubiksCube::rotate_orange_anticlockwise(self),
+            RotationDirection::HalfTurn => RubiksCube::rotate_orange_half_turn(self),
         }
     }
     pub fn rotate_white(self, direction: RotationDirection) -> RubiksCube {
         match direction {
             RotationDirection::Clockwise => RubiksCube::rotate_white_clockwise(self),
             RotationDirection::Anticlockwise => RubiksCube::rotate_white_anticlockwise(self),
+            RotationDirection::HalfTurn => RubiksCube::rotate_white_half_turn(self),
         }
     }
     pub fn rotate_yellow(self, direction: RotationDirection) -> RubiksCube {
         match direction {
             RotationDirection::Clockwise => RubiksCube::rotate_yellow_clockwise(self),
             RotationDirection::Anticlockwise => RubiksCube::rotate_yellow_anticlockwise(self),
+            RotationDirection::HalfTurn => RubiksCube::rotate_yellow_half_turn(self),
         }
     }
 
@@ -164,15 +471,15 @@ impl RubiksCube {
                 ..self.white
             },
             orange: Face {
-                left_bottom: self.white.right_bottom,
+                left_bottom: self.white.right_top,
                 left: self.white.right,
-                left_top: self.white.right_top,
+                left_top: self.white.right_bottom,
                 ..self.orange
             },
             yellow: Face {
-                right_bottom: self.orange.left_bottom,
+                right_bottom: self.orange.left_top,
                 right: self.orange.left,
-                right_top: self.orange.left_top,
+                right_top: self.orange.left_bottom,
                 ..self.yellow
             },
             ..self
@@ -188,15 +495,15 @@ impl RubiksCube {
                 ..self.red
             },
             white: Face {
-                right_bottom: self.orange.left_bottom,
+                right_bottom: self.orange.left_top,
                 right: self.orange.left,
-                right_top: self.orange.left_top,
+                right_top: self.orange.left_bottom,
                 ..self.white
             },
             orange: Face {
-                left_bottom: self.yellow.right_bottom,
+                left_bottom: self.yellow.right_top,
                 left: self.yellow.right,
-                left_top: self.yellow.right_top,
+                left_top: self.yellow.right_bottom,
                 ..self.orange
             },
             yellow: Face {
@@ -448,6 +755,294 @@ impl RubiksCube {
             ..self
         }
     }
+    pub fn rotate_red_half_turn(self) -> RubiksCube {
+        self.rotate_red_clockwise().rotate_red_clockwise()
+    }
+    pub fn rotate_blue_half_turn(self) -> RubiksCube {
+        self.rotate_blue_clockwise().rotate_blue_clockwise()
+    }
+    pub fn rotate_green_half_turn(self) -> RubiksCube {
+        self.rotate_green_clockwise().rotate_green_clockwise()
+    }
+    pub fn rotate_orange_half_turn(self) -> RubiksCube {
+        self.rotate_orange_clockwise().rotate_orange_clockwise()
+    }
+    pub fn rotate_white_half_turn(self) -> RubiksCube {
+        self.rotate_white_clockwise().rotate_white_clockwise()
+    }
+    pub fn rotate_yellow_half_turn(self) -> RubiksCube {
+        self.rotate_yellow_clockwise().rotate_yellow_clockwise()
+    }
+
+    // The middle slice turns like the green face, the equator like the
+    // yellow face and the standing slice like the red face.
+    pub fn rotate_middle_clockwise(self) -> RubiksCube {
+        RubiksCube {
+            red: Face {
+                top: self.white.top,
+                center: self.white.center,
+                bottom: self.white.bottom,
+                ..self.red
+            },
+            yellow: Face {
+                top: self.red.top,
+                center: self.red.center,
+                bottom: self.red.bottom,
+                ..self.yellow
+            },
+            orange: Face {
+                bottom: self.yellow.top,
+                center: self.yellow.center,
+                top: self.yellow.bottom,
+                ..self.orange
+            },
+            white: Face {
+                bottom: self.orange.top,
+                center: self.orange.center,
+                top: self.orange.bottom,
+                ..self.white
+            },
+            ..self
+        }
+    }
+    pub fn rotate_middle_anticlockwise(self) -> RubiksCube {
+        RubiksCube {
+            white: Face {
+                top: self.red.top,
+                center: self.red.center,
+                bottom: self.red.bottom,
+                ..self.white
+            },
+            red: Face {
+                top: self.yellow.top,
+                center: self.yellow.center,
+                bottom: self.yellow.bottom,
+                ..self.red
+            },
+            yellow: Face {
+                top: self.orange.bottom,
+                center: self.orange.center,
+                bottom: self.orange.top,
+                ..self.yellow
+            },
+            orange: Face {
+                bottom: self.white.top,
+                center: self.white.center,
+                top: self.white.bottom,
+                ..self.orange
+            },
+            ..self
+        }
+    }
+    pub fn rotate_equator_clockwise(self) -> RubiksCube {
+        RubiksCube {
+            blue: Face {
+                left: self.red.left,
+                center: self.red.center,
+                right: self.red.right,
+                ..self.blue
+            },
+            orange: Face {
+                left: self.blue.left,
+                center: self.blue.center,
+                right: self.blue.right,
+                ..self.orange
+            },
+            green: Face {
+                left: self.orange.left,
+                center: self.orange.center,
+                right: self.orange.right,
+                ..self.green
+            },
+            red: Face {
+                left: self.green.left,
+                center: self.green.center,
+                right: self.green.right,
+                ..self.red
+            },
+            ..self
+        }
+    }
+    pub fn rotate_equator_anticlockwise(self) -> RubiksCube {
+        RubiksCube {
+            red: Face {
+                left: self.blue.left,
+                center: self.blue.center,
+                right: self.blue.right,
+                ..self.red
+            },
+            blue: Face {
+                left: self.orange.left,
+                center: self.orange.center,
+                right: self.orange.right,
+                ..self.blue
+            },
+            orange: Face {
+                left: self.green.left,
+                center: self.green.center,
+                right: self.green.right,
+                ..self.orange
+            },
+            green: Face {
+                left: self.red.left,
+                center: self.red.center,
+                right: self.red.right,
+                ..self.green
+            },
+            ..self
+        }
+    }
+    pub fn rotate_standing_clockwise(self) -> RubiksCube {
+        RubiksCube {
+            white: Face {
+                left: self.green.bottom,
+                center: self.green.center,
+                right: self.green.top,
+                ..self.white
+            },
+            blue: Face {
+                top: self.white.left,
+                center: self.white.center,
+                bottom: self.white.right,
+                ..self.blue
+            },
+            yellow: Face {
+                right: self.blue.top,
+                center: self.blue.center,
+                left: self.blue.bottom,
+                ..self.yellow
+            },
+            green: Face {
+                bottom: self.yellow.right,
+                center: self.yellow.center,
+                top: self.yellow.left,
+                ..self.green
+            },
+            ..self
+        }
+    }
+    pub fn rotate_standing_anticlockwise(self) -> RubiksCube {
+        RubiksCube {
+            white: Face {
+                left: self.blue.top,
+                center: self.blue.center,
+                right: self.blue.bottom,
+                ..self.white
+            },
+            blue: Face {
+                top: self.yellow.right,
+                center: self.yellow.center,
+                bottom: self.yellow.left,
+                ..self.blue
+            },
+            yellow: Face {
+                right: self.green.bottom,
+                center: self.green.center,
+                left: self.green.top,
+                ..self.yellow
+            },
+            green: Face {
+                bottom: self.white.left,
+                center: self.white.center,
+                top: self.white.right,
+                ..self.green
+            },
+            ..self
+        }
+    }
+    pub fn rotate_middle_half_turn(self) -> RubiksCube {
+        self.rotate_middle_clockwise().rotate_middle_clockwise()
+    }
+    pub fn rotate_equator_half_turn(self) -> RubiksCube {
+        self.rotate_equator_clockwise().rotate_equator_clockwise()
+    }
+    pub fn rotate_standing_half_turn(self) -> RubiksCube {
+        self.rotate_standing_clockwise().rotate_standing_clockwise()
+    }
+
+    // Whole cube rotations turn like the blue face (x), the white face (y)
+    // and the red face (z).
+    pub fn rotate_x_clockwise(self) -> RubiksCube {
+        RubiksCube {
+            white: self.red,
+            red: self.yellow,
+            yellow: self.orange.rotate_half_turn(),
+            orange: self.white.rotate_half_turn(),
+            blue: self.blue.rotate_clockwise(),
+            green: self.green.rotate_anticlockwise(),
+        }
+    }
+    pub fn rotate_x_anticlockwise(self) -> RubiksCube {
+        RubiksCube {
+            red: self.white,
+            yellow: self.red,
+            orange: self.yellow.rotate_half_turn(),
+            white: self.orange.rotate_half_turn(),
+            blue: self.blue.rotate_anticlockwise(),
+            green: self.green.rotate_clockwise(),
+        }
+    }
+    pub fn rotate_y_clockwise(self) -> RubiksCube {
+        RubiksCube {
+            green: self.red,
+            orange: self.green,
+            blue: self.orange,
+            red: self.blue,
+            white: self.white.rotate_clockwise(),
+            yellow: self.yellow.rotate_anticlockwise(),
+        }
+    }
+    pub fn rotate_y_anticlockwise(self) -> RubiksCube {
+        RubiksCube {
+            red: self.green,
+            green: self.orange,
+            orange: self.blue,
+            blue: self.red,
+            white: self.white.rotate_anticlockwise(),
+            yellow: self.yellow.rotate_clockwise(),
+        }
+    }
+    pub fn rotate_z_clockwise(self) -> RubiksCube {
+        RubiksCube {
+            blue: self.white.rotate_clockwise(),
+            yellow: self.blue.rotate_clockwise(),
+            green: self.yellow.rotate_clockwise(),
+            white: self.green.rotate_clockwise(),
+            red: self.red.rotate_clockwise(),
+            orange: self.orange.rotate_anticlockwise(),
+        }
+    }
+    pub fn rotate_z_anticlockwise(self) -> RubiksCube {
+        RubiksCube {
+            white: self.blue.rotate_anticlockwise(),
+            blue: self.yellow.rotate_anticlockwise(),
+            yellow: self.green.rotate_anticlockwise(),
+            green: self.white.rotate_anticlockwise(),
+            red: self.red.rotate_anticlockwise(),
+            orange: self.orange.rotate_clockwise(),
+        }
+    }
+    pub fn rotate_x_half_turn(self) -> RubiksCube {
+        self.rotate_x_clockwise().rotate_x_clockwise()
+    }
+    pub fn rotate_y_half_turn(self) -> RubiksCube {
+        self.rotate_y_clockwise().rotate_y_clockwise()
+    }
+    pub fn rotate_z_half_turn(self) -> RubiksCube {
+        self.rotate_z_clockwise().rotate_z_clockwise()
+    }
+}
+
+// Where the facet is in the facet list of a solved cube. The index is
+// checked since it is public and a wrong one would name another facet.
+fn home_position(facet: &Facet) -> usize {
+    assert!(
+        (1..=9).contains(&facet.index),
+        "facet index {} is not between 1 and 9",
+        facet.index
+    );
+    let face = FACES.iter().position(|f| *f == facet.color).unwrap();
+    face * 9 + facet.index as usize - 1
 }
 
 #[cfg(test)]
@@ -496,4 +1091,494 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_half_turn_should_give_same_as_two_rotations_in_either_direction() {
+        let my_rubiks_cube = RubiksCube::new().apply(&"R U F".parse().unwrap());
+        for color in ColorFacet::iterator() {
+            let half_turned_cube = my_rubiks_cube.rotate(*color, RotationDirection::HalfTurn);
+            for direction in [
+                RotationDirection::Clockwise,
+                RotationDirection::Anticlockwise,
+            ]
+            .iter()
+            {
+                let rotated_cube = my_rubiks_cube
+                    .rotate(*color, *direction)
+                    .rotate(*color, *direction);
+                assert_eq!(half_turned_cube, rotated_cube);
+            }
+        }
+    }
+
+    #[test]
+    fn test_half_turn_should_be_its_own_opposite() {
+        assert_eq!(
+            RotationDirection::HalfTurn.opposite(),
+            RotationDirection::HalfTurn
+        );
+        let my_rubiks_cube = RubiksCube::new();
+        for color in ColorFacet::iterator() {
+            let rotated_cube = my_rubiks_cube
+                .rotate(*color, RotationDirection::HalfTurn)
+                .rotate(*color, RotationDirection::HalfTurn);
+            assert_eq!(my_rubiks_cube, rotated_cube);
+        }
+    }
+
+    #[test]
+    fn test_apply_should_match_rotate() {
+        let algorithm: Algorithm = "F R' U2".parse().unwrap();
+        let rotated_cube = RubiksCube::new()
+            .rotate(ColorFacet::Red, RotationDirection::Clockwise)
+            .rotate(ColorFacet::Blue, RotationDirection::Anticlockwise)
+            .rotate(ColorFacet::White, RotationDirection::HalfTurn);
+        assert_eq!(RubiksCube::new().apply(&algorithm), rotated_cube);
+    }
+
+    #[test]
+    fn test_sexy_move_six_times_should_give_identity() {
+        let algorithm: Algorithm = "R U R' U'".parse().unwrap();
+        let mut rotated_cube = RubiksCube::new();
+        for i in 1..=6 {
+            rotated_cube = rotated_cube.apply(&algorithm);
+            assert_eq!(rotated_cube == RubiksCube::new(), i == 6);
+        }
+    }
+
+    #[test]
+    fn test_superflip_should_flip_every_edge_in_place() {
+        let algorithm: Algorithm = "U R2 F B R B2 R U2 L B2 R U' D' R2 F R' L B2 U2 F2"
+            .parse()
+            .unwrap();
+        let cube = RubiksCube::new().apply(&algorithm);
+        assert_eq!(cube.white.top.color, ColorFacet::Orange);
+        assert_eq!(cube.orange.top.color, ColorFacet::White);
+        assert_eq!(cube.red.right.color, ColorFacet::Blue);
+        assert_eq!(cube.blue.left.color, ColorFacet::Red);
+        assert_eq!(cube.white.left_top.color, ColorFacet::White);
+        assert_eq!(cube.yellow.right_bottom.color, ColorFacet::Yellow);
+    }
+
+    #[test]
+    fn test_slice_rotations_should_give_identity_like_face_rotations() {
+        let my_rubiks_cube = RubiksCube::new();
+        for slice in Slice::iterator() {
+            for direction in RotationDirection::iterator() {
+                let back_and_forth_cube = my_rubiks_cube
+                    .rotate_slice(*slice, *direction)
+                    .rotate_slice(*slice, direction.opposite());
+                assert_eq!(my_rubiks_cube, back_and_forth_cube);
+                let rotated_cube = my_rubiks_cube
+                    .rotate_slice(*slice, *direction)
+                    .rotate_slice(*slice, *direction)
+                    .rotate_slice(*slice, *direction)
+                    .rotate_slice(*slice, *direction);
+                assert_eq!(my_rubiks_cube, rotated_cube);
+            }
+        }
+    }
+
+    #[test]
+    fn test_slice_should_move_centers() {
+        let rotated_cube =
+            RubiksCube::new().rotate_slice(Slice::Middle, RotationDirection::Clockwise);
+        assert_eq!(rotated_cube.red.center, Facet::new(ColorFacet::White, 5));
+        assert_eq!(rotated_cube.yellow.center, Facet::new(ColorFacet::Red, 5));
+        assert_eq!(
+            rotated_cube.orange.center,
+            Facet::new(ColorFacet::Yellow, 5)
+        );
+        assert_eq!(rotated_cube.white.center, Facet::new(ColorFacet::Orange, 5));
+        assert_eq!(rotated_cube.blue.center, Facet::new(ColorFacet::Blue, 5));
+    }
+
+    #[test]
+    fn test_middle_with_outer_layers_should_turn_whole_cube() {
+        // M L R' carries every layer from the white side to the red side.
+        let rotated_cube = RubiksCube::new().apply(&"M L R'".parse().unwrap());
+        assert_eq!(rotated_cube.red, Face::new(ColorFacet::White));
+        assert_eq!(rotated_cube.yellow, Face::new(ColorFacet::Red));
+        assert_eq!(
+            rotated_cube.orange,
+            Face::new(ColorFacet::Yellow).rotate_half_turn()
+        );
+        assert_eq!(
+            rotated_cube.white,
+            Face::new(ColorFacet::Orange).rotate_half_turn()
+        );
+        assert_eq!(
+            rotated_cube.blue,
+            Face::new(ColorFacet::Blue).rotate_anticlockwise()
+        );
+        assert_eq!(
+            rotated_cube.green,
+            Face::new(ColorFacet::Green).rotate_clockwise()
+        );
+    }
+
+    #[test]
+    fn test_equator_with_outer_layers_should_turn_whole_cube() {
+        let rotated_cube = RubiksCube::new().apply(&"E D U'".parse().unwrap());
+        assert_eq!(rotated_cube.blue, Face::new(ColorFacet::Red));
+        assert_eq!(rotated_cube.orange, Face::new(ColorFacet::Blue));
+        assert_eq!(rotated_cube.green, Face::new(ColorFacet::Orange));
+        assert_eq!(rotated_cube.red, Face::new(ColorFacet::Green));
+        assert_eq!(
+            rotated_cube.white,
+            Face::new(ColorFacet::White).rotate_anticlockwise()
+        );
+        assert_eq!(
+            rotated_cube.yellow,
+            Face::new(ColorFacet::Yellow).rotate_clockwise()
+        );
+    }
+
+    #[test]
+    fn test_standing_with_outer_layers_should_turn_whole_cube() {
+        let rotated_cube = RubiksCube::new().apply(&"S F B'".parse().unwrap());
+        assert_eq!(
+            rotated_cube.blue,
+            Face::new(ColorFacet::White).rotate_clockwise()
+        );
+        assert_eq!(
+            rotated_cube.yellow,
+            Face::new(ColorFacet::Blue).rotate_clockwise()
+        );
+        assert_eq!(
+            rotated_cube.green,
+            Face::new(ColorFacet::Yellow).rotate_clockwise()
+        );
+        assert_eq!(
+            rotated_cube.white,
+            Face::new(ColorFacet::Green).rotate_clockwise()
+        );
+        assert_eq!(
+            rotated_cube.red,
+            Face::new(ColorFacet::Red).rotate_clockwise()
+        );
+        assert_eq!(
+            rotated_cube.orange,
+            Face::new(ColorFacet::Orange).rotate_anticlockwise()
+        );
+    }
+
+    #[test]
+    fn test_slice_h_permutation_should_match_outer_layer_h_permutation() {
+        let with_slices = RubiksCube::new().apply(&"M2 U M2 U2 M2 U M2".parse().unwrap());
+        let with_outer_layers =
+            RubiksCube::new().apply(&"R2 U2 R U2 R2 U2 R2 U2 R U2 R2".parse().unwrap());
+        assert_eq!(with_slices, with_outer_layers);
+    }
+
+    #[test]
+    fn test_cube_rotations_should_equal_outer_layers_with_slice() {
+        let scrambled_cube = RubiksCube::new().apply(&"R U F' L2 D B M E' S".parse().unwrap());
+        let equivalences = [
+            ("x", "R M' L'"),
+            ("y", "U E' D'"),
+            ("z", "F S B'"),
+            ("x'", "R' M L"),
+            ("y2", "U2 E2 D2"),
+        ];
+        for (rotation, layers) in equivalences.iter() {
+            assert_eq!(
+                scrambled_cube.apply(&rotation.parse().unwrap()),
+                scrambled_cube.apply(&layers.parse().unwrap()),
+                "{}",
+                rotation
+            );
+        }
+    }
+
+    #[test]
+    fn test_cube_rotations_should_give_identity_like_face_rotations() {
+        let my_rubiks_cube = RubiksCube::new();
+        for axis in Axis::iterator() {
+            for direction in RotationDirection::iterator() {
+                let back_and_forth_cube = my_rubiks_cube
+                    .rotate_cube(*axis, *direction)
+                    .rotate_cube(*axis, direction.opposite());
+                assert_eq!(my_rubiks_cube, back_and_forth_cube);
+                let rotated_cube = my_rubiks_cube
+                    .rotate_cube(*axis, *direction)
+                    .rotate_cube(*axis, *direction)
+                    .rotate_cube(*axis, *direction)
+                    .rotate_cube(*axis, *direction);
+                assert_eq!(my_rubiks_cube, rotated_cube);
+            }
+        }
+    }
+
+    #[test]
+    fn test_orientation_should_follow_cube_rotations() {
+        let my_rubiks_cube = RubiksCube::new();
+        assert_eq!(my_rubiks_cube.up(), ColorFacet::White);
+        assert_eq!(my_rubiks_cube.front(), ColorFacet::Red);
+        let rotated_cube = my_rubiks_cube.apply(&"x".parse().unwrap());
+        assert_eq!(rotated_cube.up(), ColorFacet::Red);
+        assert_eq!(rotated_cube.front(), ColorFacet::Yellow);
+        let rotated_cube = my_rubiks_cube.apply(&"y z'".parse().unwrap());
+        assert_eq!(rotated_cube.up(), ColorFacet::Orange);
+        assert_eq!(rotated_cube.front(), ColorFacet::Blue);
+    }
+
+    #[test]
+    fn test_moves_after_rotation_should_be_relative_to_new_orientation() {
+        let my_rubiks_cube = RubiksCube::new();
+        assert_eq!(
+            my_rubiks_cube.apply(&"y R U'".parse().unwrap()),
+            my_rubiks_cube.apply(&"B U' y".parse().unwrap())
+        );
+        assert_eq!(
+            my_rubiks_cube.apply(&"x F".parse().unwrap()),
+            my_rubiks_cube.apply(&"D x".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_wide_moves_should_equal_outer_layer_with_slice() {
+        let scrambled_cube = RubiksCube::new().apply(&"R U F' L2 D B".parse().unwrap());
+        let definitions = [
+            ("Rw", "R M'"),
+            ("Lw", "L M"),
+            ("Uw", "U E'"),
+            ("Dw", "D E"),
+            ("Fw", "F S"),
+            ("Bw", "B S'"),
+        ];
+        for (wide, layers) in definitions.iter() {
+            let wide: Move = wide.parse().unwrap();
+            let layers: Algorithm = layers.parse().unwrap();
+            for direction in RotationDirection::iterator() {
+                let with_direction = |cube_move: Move| match cube_move {
+                    Move::Face(face, _) => Move::Face(face, *direction),
+                    Move::Slice(slice, RotationDirection::Clockwise) => {
+                        Move::Slice(slice, *direction)
+                    }
+                    Move::Slice(slice, _) => Move::Slice(slice, direction.opposite()),
+                    Move::Wide(face, _) => Move::Wide(face, *direction),
+                    Move::Rotation(axis, _) => Move::Rotation(axis, *direction),
+                };
+                let expected_cube = layers.moves.iter().fold(scrambled_cube, |cube, cube_move| {
+                    cube.apply_move(with_direction(*cube_move))
+                });
+                assert_eq!(
+                    scrambled_cube.apply_move(with_direction(wide)),
+                    expected_cube,
+                    "{} {:?}",
+                    wide,
+                    direction
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_wide_move_should_equal_opposite_face_with_cube_rotation() {
+        let my_rubiks_cube = RubiksCube::new();
+        assert_eq!(
+            my_rubiks_cube.apply(&"r".parse().unwrap()),
+            my_rubiks_cube.apply(&"L x".parse().unwrap())
+        );
+        assert_eq!(
+            my_rubiks_cube.apply(&"u'".parse().unwrap()),
+            my_rubiks_cube.apply(&"D' y'".parse().unwrap())
+        );
+        assert_eq!(
+            my_rubiks_cube.apply(&"f2".parse().unwrap()),
+            my_rubiks_cube.apply(&"B2 z2".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_wide_oll_should_keep_first_two_layers() {
+        let cube = RubiksCube::new().apply(&"r U R' U' r' F R F'".parse().unwrap());
+        for face in [cube.red, cube.blue, cube.green, cube.orange].iter() {
+            assert!([
+                face.left,
+                face.center,
+                face.right,
+                face.left_bottom,
+                face.bottom
+            ]
+            .iter()
+            .all(|facet| facet.color == face.center.color));
+        }
+        assert_eq!(cube.yellow, Face::new(ColorFacet::Yellow));
+    }
+
+    #[test]
+    fn test_validate_should_accept_reachable_cubes() {
+        assert_eq!(RubiksCube::new().validate(), Ok(()));
+        let scrambled_cube = RubiksCube::new().apply(&"R U F' L2 D B M E' S x Rw".parse().unwrap());
+        assert_eq!(scrambled_cube.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_should_count_colors() {
+        let mut my_rubiks_cube = RubiksCube::new();
+        for color in [ColorFacet::Blue, ColorFacet::Green, ColorFacet::White].iter() {
+            my_rubiks_cube = my_rubiks_cube.with_face(*color, Face::new(ColorFacet::Red));
+        }
+        assert_eq!(
+            my_rubiks_cube.validate(),
+            Err(ValidationError::WrongColorCount(ColorFacet::Red, 36))
+        );
+    }
+
+    #[test]
+    fn test_validate_should_find_twisted_corner() {
+        let mut my_rubiks_cube = RubiksCube::new();
+        my_rubiks_cube.white.right_bottom.color = ColorFacet::Blue;
+        my_rubiks_cube.blue.left_top.color = ColorFacet::Red;
+        my_rubiks_cube.red.right_top.color = ColorFacet::White;
+        assert_eq!(
+            my_rubiks_cube.validate(),
+            Err(ValidationError::TwistedCorner)
+        );
+    }
+
+    #[test]
+    fn test_validate_should_find_flipped_edge() {
+        let mut my_rubiks_cube = RubiksCube::new();
+        my_rubiks_cube.white.top.color = ColorFacet::Orange;
+        my_rubiks_cube.orange.top.color = ColorFacet::White;
+        assert_eq!(my_rubiks_cube.validate(), Err(ValidationError::FlippedEdge));
+    }
+
+    #[test]
+    fn test_validate_should_find_swapped_edges() {
+        let mut my_rubiks_cube = RubiksCube::new();
+        my_rubiks_cube.red.top.color = ColorFacet::Blue;
+        my_rubiks_cube.blue.top.color = ColorFacet::Red;
+        assert_eq!(
+            my_rubiks_cube.validate(),
+            Err(ValidationError::PermutationParity)
+        );
+    }
+
+    #[test]
+    fn test_validate_should_find_missing_cubies() {
+        let mut my_rubiks_cube = RubiksCube::new();
+        my_rubiks_cube.blue.left_top.color = ColorFacet::Red;
+        my_rubiks_cube.red.right_top.color = ColorFacet::Green;
+        my_rubiks_cube.green.top.color = ColorFacet::Blue;
+        assert_eq!(
+            my_rubiks_cube.validate(),
+            Err(ValidationError::MissingCorner(crate::Corner::Urf))
+        );
+    }
+
+    const SOLVED_FACELETS: &str = "UUUUUUUUURRRRRRRRRFFFFFFFFFDDDDDDDDDLLLLLLLLLBBBBBBBBB";
+
+    #[test]
+    fn test_to_facelets_should_list_faces_in_urfdlb_order() {
+        assert_eq!(RubiksCube::new().to_facelets(), SOLVED_FACELETS);
+        assert_eq!(
+            RubiksCube::new().apply(&"R".parse().unwrap()).to_facelets(),
+            "UUFUUFUUFRRRRRRRRRFFDFFDFFDDDBDDBDDBLLLLLLLLLUBBUBBUBB"
+        );
+    }
+
+    #[test]
+    fn test_from_facelets_should_read_back_what_to_facelets_writes() {
+        let algorithm: Algorithm = "R U' F2 M' D L2 B' E S2 r U2 x y'".parse().unwrap();
+        let my_rubiks_cube = RubiksCube::new().apply(&algorithm);
+        assert_eq!(
+            RubiksCube::from_facelets(&my_rubiks_cube.to_facelets()),
+            Ok(my_rubiks_cube)
+        );
+        assert_eq!(
+            RubiksCube::from_facelets(SOLVED_FACELETS),
+            Ok(RubiksCube::new())
+        );
+    }
+
+    #[test]
+    fn test_from_facelets_should_use_the_color_scheme() {
+        let scheme = ColorScheme {
+            up: ColorFacet::Yellow,
+            right: ColorFacet::Orange,
+            front: ColorFacet::Green,
+            down: ColorFacet::White,
+            left: ColorFacet::Red,
+            back: ColorFacet::Blue,
+        };
+        let my_rubiks_cube = RubiksCube::new().apply(&"x2 y'".parse().unwrap());
+        assert_eq!(
+            RubiksCube::from_facelets_with_scheme(SOLVED_FACELETS, &scheme),
+            Ok(my_rubiks_cube)
+        );
+        assert_eq!(
+            my_rubiks_cube.to_facelets_with_scheme(&scheme),
+            Ok(SOLVED_FACELETS.to_string())
+        );
+    }
+
+    #[test]
+    fn test_from_facelets_should_reject_malformed_input() {
+        assert_eq!(
+            RubiksCube::from_facelets("UUU"),
+            Err(FaceletError::WrongLength(3))
+        );
+        let mut facelets = SOLVED_FACELETS.to_string();
+        facelets.replace_range(10..11, "X");
+        assert_eq!(
+            RubiksCube::from_facelets(&facelets),
+            Err(FaceletError::UnknownLetter(10, 'X'))
+        );
+        facelets.replace_range(10..11, "U");
+        assert_eq!(
+            RubiksCube::from_facelets(&facelets),
+            Err(FaceletError::Unsolvable(ValidationError::WrongColorCount(
+                ColorFacet::Blue,
+                8
+            )))
+        );
+        let scheme = ColorScheme {
+            up: ColorFacet::Red,
+            ..ColorScheme::default()
+        };
+        assert_eq!(
+            RubiksCube::from_facelets_with_scheme(SOLVED_FACELETS, &scheme),
+            Err(FaceletError::InvalidScheme)
+        );
+    }
+
+    #[test]
+    fn test_mul_should_compose_like_algorithms() {
+        let pairs = [
+            ("R U R' U'", "F2 D' L"),
+            ("M' E S2 x", "r U2 y' B"),
+            ("", "R"),
+        ];
+        for (first, second) in pairs.iter() {
+            let first: Algorithm = first.parse().unwrap();
+            let second: Algorithm = second.parse().unwrap();
+            let a = RubiksCube::new().apply(&first);
+            let b = RubiksCube::new().apply(&second);
+            assert_eq!(a * b, RubiksCube::new().apply(&first.then(&second)));
+            assert_eq!(a.inverse(), RubiksCube::new().apply(&first.inverse()));
+            assert_eq!(a * a.inverse(), RubiksCube::identity());
+            assert_eq!(a.inverse() * a, RubiksCube::identity());
+            assert_eq!(RubiksCube::identity() * b, b);
+            assert_eq!((a * b) * a, a * (b * a));
+        }
+        let sexy = RubiksCube::new().apply(&"R U R' U'".parse().unwrap());
+        let product = (0..6).fold(RubiksCube::identity(), |cube, _| cube * sexy);
+        assert_eq!(product, RubiksCube::identity());
+    }
+
+    #[test]
+    fn test_same_colors_should_ignore_facet_indexes() {
+        let cube = RubiksCube::new();
+        let mut facets = cube.white.facets();
+        facets.swap(0, 2);
+        let swapped = cube.with_face(ColorFacet::White, Face::from_facets(facets));
+        assert!(swapped.same_colors(&cube));
+        assert_ne!(swapped, cube);
+        let turned = cube.apply(&"U".parse().unwrap());
+        assert!(!turned.same_colors(&cube));
+    }
 }
@@ -0,0 +1,14 @@
+use crate::Algorithm;
+use crate::RubiksCube;
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct Scramble {
+    pub algorithm: Algorithm,
+    pub cube: RubiksCube,
+}
+
+impl std::fmt::Display for Scramble {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.algorithm)
+    }
+}
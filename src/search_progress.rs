@@ -0,0 +1,6 @@
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct SearchProgress {
+    pub depth: usize,
+    pub nodes: u64,
+    pub solutions: usize,
+}
@@ -0,0 +1,19 @@
+use crate::Algorithm;
+use crate::MoveCount;
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct Simplification {
+    pub algorithm: Algorithm,
+    pub before: MoveCount,
+    pub after: MoveCount,
+}
+
+impl std::fmt::Display for Simplification {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{} ({} before, {} after)",
+            self.algorithm, self.before, self.after
+        )
+    }
+}
@@ -0,0 +1,51 @@
+use crate::Axis;
+use crate::ColorFacet;
+use std::slice::Iter;
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Slice {
+    Middle,
+    Equator,
+    Standing,
+}
+
+impl Slice {
+    pub fn iterator() -> Iter<'static, Slice> {
+        static SLICE: [Slice; 3] = [Slice::Middle, Slice::Equator, Slice::Standing];
+        SLICE.iter()
+    }
+
+    pub fn from_notation(letter: char) -> Option<Slice> {
+        match letter {
+            'M' => Some(Slice::Middle),
+            'E' => Some(Slice::Equator),
+            'S' => Some(Slice::Standing),
+            _ => None,
+        }
+    }
+
+    pub fn axis(self) -> Axis {
+        match self {
+            Slice::Middle => Axis::X,
+            Slice::Equator => Axis::Y,
+            Slice::Standing => Axis::Z,
+        }
+    }
+
+    // The face the slice turns the same way as.
+    pub fn face(self) -> ColorFacet {
+        match self {
+            Slice::Middle => ColorFacet::Green,
+            Slice::Equator => ColorFacet::Yellow,
+            Slice::Standing => ColorFacet::Red,
+        }
+    }
+
+    pub fn notation(self) -> char {
+        match self {
+            Slice::Middle => 'M',
+            Slice::Equator => 'E',
+            Slice::Standing => 'S',
+        }
+    }
+}
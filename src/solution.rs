@@ -0,0 +1,39 @@
+use crate::Algorithm;
+use crate::SolutionStage;
+
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct Solution {
+    pub stages: Vec<SolutionStage>,
+}
+
+impl std::fmt::Display for Solution {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for stage in self.stages.iter() {
+            writeln!(f, "{}: {} ({})", stage.name, stage.moves, stage.description)?;
+        }
+        Ok(())
+    }
+}
+
+impl Solution {
+    pub fn algorithm(&self) -> Algorithm {
+        let moves = self
+            .stages
+            .iter()
+            .flat_map(|stage| stage.moves.moves.clone())
+            .collect();
+        Algorithm::new(moves)
+    }
+
+    pub fn len(&self) -> usize {
+        self.stages.iter().map(|stage| stage.moves.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn stage(&self, name: &str) -> Option<&SolutionStage> {
+        self.stages.iter().find(|stage| stage.name == name)
+    }
+}
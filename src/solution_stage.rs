@@ -0,0 +1,8 @@
+use crate::Algorithm;
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct SolutionStage {
+    pub name: String,
+    pub moves: Algorithm,
+    pub description: String,
+}
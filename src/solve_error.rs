@@ -0,0 +1,24 @@
+use crate::ValidationError;
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum SolveError {
+    Unsolvable(ValidationError),
+    NotFound,
+}
+
+impl std::fmt::Display for SolveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            SolveError::Unsolvable(error) => write!(f, "{}", error),
+            SolveError::NotFound => write!(f, "no solution short enough found in time"),
+        }
+    }
+}
+
+impl std::error::Error for SolveError {}
+
+impl From<ValidationError> for SolveError {
+    fn from(error: ValidationError) -> Self {
+        SolveError::Unsolvable(error)
+    }
+}
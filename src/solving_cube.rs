@@ -0,0 +1,233 @@
+use crate::cubie_cube::{CORNER_FACELETS, EDGE_FACELETS, FACES};
+use crate::pattern_databases::{PatternDatabase, PieceKind};
+use crate::Algorithm;
+use crate::Axis;
+use crate::ColorFacet;
+use crate::CubieCube;
+use crate::Move;
+use crate::RotationDirection;
+use crate::RubiksCube;
+use crate::Slice;
+use crate::Solution;
+use crate::SolutionStage;
+use crate::SolveError;
+use std::sync::OnceLock;
+
+// Side faces clockwise seen from above, starting with the front. An
+// algorithm applied from side k is turned as if that side were the front.
+pub(crate) const SIDES: [usize; 4] = [2, 1, 5, 4];
+
+// The side each first and second layer slot is worked on from, for the
+// slots of the front right, front left, back left and back right.
+pub(crate) const SLOT_SIDES: [usize; 4] = [0, 3, 2, 1];
+
+const DOWN: usize = 3;
+
+// Turns of the up face, indexed by quarter turns clockwise.
+pub(crate) const UP_TURNS: [&str; 4] = ["", "U", "U2", "U'"];
+
+// A cube being solved by a method, collecting the moves of each stage.
+// Pieces are numbered as in `CubieCube` and faces as in `FACES`.
+pub(crate) struct SolvingCube {
+    pub(crate) cube: CubieCube,
+    colors: Vec<ColorFacet>,
+    moves: Vec<Move>,
+    stages: Vec<SolutionStage>,
+}
+
+impl SolvingCube {
+    pub(crate) fn new(cube: &RubiksCube) -> Result<SolvingCube, SolveError> {
+        cube.validate()?;
+        Ok(SolvingCube {
+            cube: CubieCube::from_rubiks_cube(cube)?,
+            colors: FACES
+                .iter()
+                .map(|face| cube.face(*face).center.color)
+                .collect(),
+            moves: Vec::new(),
+            stages: Vec::new(),
+        })
+    }
+
+    pub(crate) fn apply(&mut self, algorithm: &str) {
+        self.apply_from(0, algorithm);
+    }
+
+    pub(crate) fn apply_from(&mut self, side: usize, algorithm: &str) {
+        let algorithm = from_side(side, algorithm);
+        self.cube = self.cube.apply(&algorithm);
+        self.moves.extend(algorithm.moves);
+    }
+
+    pub(crate) fn apply_algorithm(&mut self, algorithm: &Algorithm) {
+        self.cube = self.cube.apply(algorithm);
+        self.moves.extend(algorithm.moves.iter().cloned());
+    }
+
+    // The cube as it would be after the algorithm, without applying it.
+    pub(crate) fn after(&self, side: usize, algorithm: &str) -> CubieCube {
+        self.cube.apply(&from_side(side, algorithm))
+    }
+
+    pub(crate) fn finish_stage(&mut self, name: &str, description: String) {
+        self.stages.push(SolutionStage {
+            name: name.to_string(),
+            moves: Algorithm::new(std::mem::take(&mut self.moves)),
+            description,
+        });
+    }
+
+    pub(crate) fn into_solution(self) -> Solution {
+        Solution {
+            stages: self.stages,
+        }
+    }
+
+    pub(crate) fn color(&self, face: usize) -> ColorFacet {
+        self.colors[face]
+    }
+
+    pub(crate) fn corner_name(&self, corner: usize) -> String {
+        let names: Vec<&str> = CORNER_FACELETS[corner]
+            .iter()
+            .map(|(face, _)| self.colors[*face].name())
+            .collect();
+        names.join("-")
+    }
+
+    pub(crate) fn edge_name(&self, edge: usize) -> String {
+        let names: Vec<&str> = EDGE_FACELETS[edge]
+            .iter()
+            .map(|(face, _)| self.colors[*face].name())
+            .collect();
+        names.join("-")
+    }
+}
+
+fn cross_table() -> &'static PatternDatabase {
+    static CROSS: OnceLock<PatternDatabase> = OnceLock::new();
+    CROSS.get_or_init(|| {
+        let moves: Vec<usize> = (0..18).collect();
+        PatternDatabase::new(PieceKind::Edge, (4..8).collect(), &moves)
+    })
+}
+
+// Solves the down edges in as few moves as possible, as the first stage of
+// the layer by layer and CFOP methods.
+pub(crate) fn solve_cross(solving: &mut SolvingCube) {
+    let table = cross_table();
+    let placed = (4..8).filter(|e| edge_solved(&solving.cube, *e)).count();
+    while table.depth(&solving.cube) > 0 {
+        let depth = table.depth(&solving.cube);
+        let cube_move = (0..18)
+            .map(Move::face_turn)
+            .find(|m| table.depth(&solving.cube.apply_move(*m)) < depth)
+            .unwrap();
+        solving.apply_algorithm(&Algorithm::new(vec![cube_move]));
+    }
+    let description = format!(
+        "{} cross on the down face, {} of 4 edges were already in place",
+        solving.color(DOWN).name(),
+        placed
+    );
+    solving.finish_stage("cross", description);
+}
+
+pub(crate) fn corner_position(cube: &CubieCube, corner: usize) -> usize {
+    cube.corner_permutation
+        .iter()
+        .position(|c| *c as usize == corner)
+        .unwrap()
+}
+
+pub(crate) fn edge_position(cube: &CubieCube, edge: usize) -> usize {
+    cube.edge_permutation
+        .iter()
+        .position(|e| *e as usize == edge)
+        .unwrap()
+}
+
+pub(crate) fn corner_solved(cube: &CubieCube, corner: usize) -> bool {
+    cube.corner_permutation[corner] as usize == corner && cube.corner_orientation[corner] == 0
+}
+
+pub(crate) fn edge_solved(cube: &CubieCube, edge: usize) -> bool {
+    cube.edge_permutation[edge] as usize == edge && cube.edge_orientation[edge] == 0
+}
+
+pub(crate) fn from_side(side: usize, algorithm: &str) -> Algorithm {
+    let algorithm: Algorithm = algorithm.parse().unwrap();
+    Algorithm::new(
+        algorithm
+            .moves
+            .iter()
+            .map(|cube_move| move_from_side(side, *cube_move))
+            .collect(),
+    )
+}
+
+// Seen from side k the cube is turned k quarter turns like y, so the side
+// faces shift along `SIDES`, and so do the slices and axes lying along them.
+fn move_from_side(side: usize, cube_move: Move) -> Move {
+    let face_from_side = |face: ColorFacet| {
+        let index = FACES.iter().position(|f| *f == face).unwrap();
+        match SIDES.iter().position(|s| *s == index) {
+            Some(k) => FACES[SIDES[(k + side) % 4]],
+            None => face,
+        }
+    };
+    // A turn about the x axis (like R) or the z axis (like F) seen from the
+    // side is a turn about one of them seen from the front.
+    let along_axis = |x_axis: bool, direction: RotationDirection| match (side % 4, x_axis) {
+        (0, _) => (x_axis, direction),
+        (2, _) => (x_axis, direction.opposite()),
+        (1, true) | (3, false) => (!x_axis, direction.opposite()),
+        _ => (!x_axis, direction),
+    };
+    match cube_move {
+        Move::Face(face, direction) => Move::Face(face_from_side(face), direction),
+        Move::Wide(face, direction) => Move::Wide(face_from_side(face), direction),
+        Move::Slice(Slice::Equator, _) | Move::Rotation(Axis::Y, _) => cube_move,
+        Move::Slice(slice, direction) => {
+            // M turns like L, against x, while S turns like F, with z.
+            let x_like = slice == Slice::Middle;
+            let along_x = if x_like {
+                direction.opposite()
+            } else {
+                direction
+            };
+            let (x_like, along) = along_axis(x_like, along_x);
+            if x_like {
+                Move::Slice(Slice::Middle, along.opposite())
+            } else {
+                Move::Slice(Slice::Standing, along)
+            }
+        }
+        Move::Rotation(axis, direction) => {
+            let (x_like, along) = along_axis(axis == Axis::X, direction);
+            Move::Rotation(if x_like { Axis::X } else { Axis::Z }, along)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_side_should_match_turning_the_cube() {
+        let algorithm = "R U F' M S2 E' x z' y r";
+        for side in 0..4 {
+            let rotation = ["", "y", "y2", "y'"][side];
+            let undo = ["", "y'", "y2", "y"][side];
+            let expected = RubiksCube::new()
+                .apply(&rotation.parse().unwrap())
+                .apply(&algorithm.parse().unwrap())
+                .apply(&undo.parse().unwrap());
+            assert_eq!(
+                RubiksCube::new().apply(&from_side(side, algorithm)),
+                expected
+            );
+        }
+    }
+}
@@ -0,0 +1,132 @@
+use crate::cube::face_index;
+use crate::Algorithm;
+use crate::ColorFacet;
+use crate::Face;
+use crate::Facet;
+use crate::Move;
+use crate::RubiksCube;
+
+// A cube whose centers show which way they are turned, as on picture cubes
+// and supercubes. `center_twists` gives for each face, in the order of
+// `ColorFacet::iterator`, the clockwise quarter turns of the center on it.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct SuperCube {
+    pub cube: RubiksCube,
+    pub center_twists: [u8; 6],
+}
+
+impl Default for SuperCube {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<RubiksCube> for SuperCube {
+    fn from(cube: RubiksCube) -> Self {
+        SuperCube {
+            cube,
+            center_twists: [0; 6],
+        }
+    }
+}
+
+impl SuperCube {
+    pub fn new() -> SuperCube {
+        SuperCube::from(RubiksCube::new())
+    }
+
+    pub fn center_twist(&self, face: ColorFacet) -> u8 {
+        self.center_twists[face_index(face)]
+    }
+
+    // Solved only when every center is also turned straight, once the cube
+    // is held in the home orientation.
+    pub fn is_solved(&self) -> bool {
+        match self.cube.orientation() {
+            Some(rotations) => {
+                let held = self.apply(&rotations.inverse());
+                held.cube.is_solved() && held.center_twists.iter().all(|twist| *twist == 0)
+            }
+            None => false,
+        }
+    }
+
+    pub fn apply(self, algorithm: &Algorithm) -> SuperCube {
+        algorithm
+            .moves
+            .iter()
+            .fold(self, |cube, cube_move| cube.apply_move(*cube_move))
+    }
+
+    pub fn apply_move(self, cube_move: Move) -> SuperCube {
+        let mut center_twists = [0; 6];
+        for (i, (from, turns)) in center_turns(cube_move).iter().enumerate() {
+            center_twists[i] = (self.center_twists[*from] + turns) % 4;
+        }
+        SuperCube {
+            cube: self.cube.apply_move(cube_move),
+            center_twists,
+        }
+    }
+}
+
+// Edge facets around a center, clockwise from the top, as `Face` indexes
+// them.
+const AROUND: [u8; 4] = [2, 6, 8, 4];
+
+fn around(face: &Face) -> [Facet; 4] {
+    [face.top, face.right, face.bottom, face.left]
+}
+
+// For each face, which face its center comes from with the move and how
+// many clockwise quarter turns it makes. An edge facet next to a center
+// always moves along with it, which gives the turn.
+fn center_turns(cube_move: Move) -> [(usize, u8); 6] {
+    let moved = RubiksCube::new().apply_move(cube_move);
+    let mut turns = [(0, 0); 6];
+    for (i, face) in ColorFacet::iterator().enumerate() {
+        let content = moved.face(*face);
+        let from = content.center.color;
+        turns[i] = around(&content)
+            .iter()
+            .enumerate()
+            .find_map(|(to, facet)| {
+                let start = AROUND.iter().position(|index| *index == facet.index)?;
+                if facet.color != from {
+                    return None;
+                }
+                Some((face_index(from), ((to + 4 - start) % 4) as u8))
+            })
+            .unwrap();
+    }
+    turns
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn after(algorithm: &str) -> SuperCube {
+        SuperCube::new().apply(&algorithm.parse().unwrap())
+    }
+
+    #[test]
+    fn test_apply_move_should_turn_the_centers_it_moves() {
+        assert_eq!(after("U R2 F'").center_twists, [3, 2, 0, 0, 1, 0]);
+        assert_eq!(after("y").center_twists, [0, 0, 0, 0, 1, 3]);
+        assert_eq!(after("S").center_twists, [0, 1, 1, 0, 1, 1]);
+        assert_eq!(after("M M'"), SuperCube::new());
+    }
+
+    #[test]
+    fn test_is_solved_should_check_the_centers() {
+        assert!(SuperCube::new().is_solved());
+        assert!(after("x y2 z'").is_solved());
+        assert!(!after("U").is_solved());
+        let twisted = after("U R L U2 R' L' U R L U2 R' L'");
+        assert!(twisted.cube.is_solved());
+        assert_eq!(twisted.center_twist(ColorFacet::White), 2);
+        assert!(!twisted.is_solved());
+        assert_ne!(twisted, SuperCube::new());
+    }
+}
@@ -0,0 +1,184 @@
+use crate::cube_move::held_face_turn;
+use crate::Algorithm;
+use crate::RubiksCube;
+use crate::Solution;
+use crate::SolutionStage;
+use crate::SolveError;
+use crate::SuperCube;
+use crate::TwoPhaseSolver;
+use crate::ValidationError;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+// Turns the up center half a turn, leaving everything else in place.
+const UP_HALF_TURN: &str = "U R L U2 R' L' U R L U2 R' L'";
+// Turns the up and right centers a quarter turn clockwise each.
+const UP_RIGHT_QUARTER_TURNS: &str = "R U2 R2 U R2 U2 R' U2 R2 U R2 U2 R U2 R2 U' R2 U2";
+
+#[derive(Debug, Clone)]
+pub struct SuperCubeSolver {
+    solver: TwoPhaseSolver,
+}
+
+impl Default for SuperCubeSolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// An algorithm turning only centers, with the quarter turns it gives the
+// center of each face.
+struct CenterAlgorithm {
+    algorithm: Algorithm,
+    twists: [u8; 6],
+}
+
+impl SuperCubeSolver {
+    pub fn new() -> SuperCubeSolver {
+        SuperCubeSolver {
+            solver: TwoPhaseSolver::new(),
+        }
+    }
+
+    // Solves the cube as a 3x3, then turns the centers straight with
+    // algorithms leaving the rest of the cube solved.
+    pub fn solve(&self, cube: &SuperCube) -> Result<Solution, SolveError> {
+        let pieces = self.solver.solve(&cube.cube)?;
+        let solved = cube.apply(&pieces);
+        // Face turns leave the centers where they are, so the twists to reach
+        // are those of a cube only rotated the way this one is held.
+        let rotations = solved
+            .cube
+            .orientation()
+            .ok_or(ValidationError::InvalidCenters)?;
+        let goal = SuperCube::new().apply(&rotations).center_twists;
+        let centers =
+            center_moves(solved.center_twists, goal).ok_or(ValidationError::TwistedCenter)?;
+        let count = centers.len();
+        let centers = centers
+            .iter()
+            .fold(Algorithm::new(Vec::new()), |moves, algorithm| {
+                moves.then(&center_algorithms()[*algorithm].algorithm)
+            });
+        Ok(Solution {
+            stages: vec![
+                SolutionStage {
+                    name: "3x3".to_string(),
+                    moves: pieces,
+                    description: "corners and edges solved".to_string(),
+                },
+                SolutionStage {
+                    name: "center twists".to_string(),
+                    moves: centers.simplify().algorithm,
+                    description: format!("{} algorithms to turn the centers straight", count),
+                },
+            ],
+        })
+    }
+}
+
+// The fewest center algorithms taking the twists to the goal, found by a
+// breadth first search over the twists of the six centers.
+fn center_moves(twists: [u8; 6], goal: [u8; 6]) -> Option<Vec<usize>> {
+    let mut parents: HashMap<[u8; 6], (usize, [u8; 6])> = HashMap::new();
+    let mut layer = vec![twists];
+    // The start has no parent, so it is reached without being in `parents`.
+    while goal != twists && !parents.contains_key(&goal) {
+        let mut next_layer = Vec::new();
+        for current in layer {
+            for (i, algorithm) in center_algorithms().iter().enumerate() {
+                let mut next = current;
+                for (twist, turns) in next.iter_mut().zip(algorithm.twists.iter()) {
+                    *twist = (*twist + turns) % 4;
+                }
+                if next != twists && !parents.contains_key(&next) {
+                    parents.insert(next, (i, current));
+                    next_layer.push(next);
+                }
+            }
+        }
+        if next_layer.is_empty() {
+            return None;
+        }
+        layer = next_layer;
+    }
+    let mut moves = Vec::new();
+    let mut current = goal;
+    while let Some((algorithm, parent)) = parents.get(&current) {
+        moves.insert(0, *algorithm);
+        current = *parent;
+    }
+    Some(moves)
+}
+
+// Both algorithms and their inverses, turned to start from each of the 24
+// ways to hold the cube.
+fn center_algorithms() -> &'static Vec<CenterAlgorithm> {
+    static ALGORITHMS: OnceLock<Vec<CenterAlgorithm>> = OnceLock::new();
+    ALGORITHMS.get_or_init(|| {
+        let mut algorithms: Vec<CenterAlgorithm> = Vec::new();
+        for text in [UP_HALF_TURN, UP_RIGHT_QUARTER_TURNS].iter() {
+            let algorithm: Algorithm = text.parse().unwrap();
+            for rotations in RubiksCube::orientations() {
+                for algorithm in [algorithm.clone(), algorithm.inverse()].iter() {
+                    let algorithm = Algorithm::new(
+                        algorithm
+                            .moves
+                            .iter()
+                            .map(|m| held_face_turn(&rotations, *m))
+                            .collect(),
+                    );
+                    let twists = SuperCube::new().apply(&algorithm).center_twists;
+                    if !algorithms.iter().any(|a| a.twists == twists) {
+                        algorithms.push(CenterAlgorithm { algorithm, twists });
+                    }
+                }
+            }
+        }
+        algorithms
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn after(algorithm: &str) -> SuperCube {
+        SuperCube::new().apply(&algorithm.parse().unwrap())
+    }
+
+    #[test]
+    fn test_solve_should_turn_the_centers_straight() {
+        let solver = SuperCubeSolver::new();
+        let scrambles = [
+            "U",
+            UP_HALF_TURN,
+            UP_RIGHT_QUARTER_TURNS,
+            "F2 D' L B2 R' U2 F' D2 L2 B R2 U' F L' D B' R U2 L F'",
+            "M' E S2 r U2 x y' F' D",
+        ];
+        for scramble in scrambles.iter() {
+            let cube = after(scramble);
+            let solution = solver.solve(&cube).unwrap();
+            assert!(
+                cube.apply(&solution.algorithm()).is_solved(),
+                "{}",
+                scramble
+            );
+        }
+        let twisted = after(UP_HALF_TURN);
+        let solution = solver.solve(&twisted).unwrap();
+        assert!(solution.stage("3x3").unwrap().moves.is_empty());
+        assert_eq!(solution.stage("center twists").unwrap().moves.len(), 12);
+    }
+
+    #[test]
+    fn test_solve_should_reject_a_single_quarter_twist() {
+        let mut cube = SuperCube::new();
+        cube.center_twists[0] = 1;
+        assert_eq!(
+            SuperCubeSolver::new().solve(&cube),
+            Err(SolveError::Unsolvable(ValidationError::TwistedCenter))
+        );
+    }
+}
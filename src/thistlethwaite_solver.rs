@@ -0,0 +1,294 @@
+use crate::solving_cube::SolvingCube;
+use crate::two_phase_tables::{FLIPS, SLICES, TWISTS};
+use crate::Algorithm;
+use crate::CubieCube;
+use crate::Move;
+use crate::RubiksCube;
+use crate::Solution;
+use crate::SolveError;
+use std::sync::OnceLock;
+
+// Moves of each phase, numbered as in `Move::face_turn`: every turn, then
+// half turns of the front and back, then also of the left and right, and
+// at last only half turns. Each phase ends in the group the next one uses.
+const PHASE_MOVES: [&[usize]; 4] = [
+    &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17],
+    &[0, 1, 2, 3, 4, 5, 7, 9, 10, 11, 12, 13, 14, 16],
+    &[0, 1, 2, 4, 7, 9, 10, 11, 13, 16],
+    &[1, 4, 7, 10, 13, 16],
+];
+
+const PHASE_DESCRIPTIONS: [&str; 4] = [
+    "edges oriented, the cube is in G1 = <U, D, L, R, F2, B2>",
+    "corners oriented and middle layer edges in their slice, the cube is in G2 = <U, D, L2, R2, F2, B2>",
+    "corners in their tetrads and edges in their slices, the cube is in G3 = <U2, D2, L2, R2, F2, B2>",
+    "solved with half turns",
+];
+
+// Up and down layer edges of the slices between the left and right faces
+// and between the front and back faces.
+const M_EDGES: [usize; 4] = [1, 3, 5, 7];
+const S_EDGES: [usize; 4] = [0, 2, 4, 6];
+const E_EDGES: [usize; 4] = [8, 9, 10, 11];
+
+const CORNER_PERMUTATIONS: usize = 40320;
+const UNKNOWN: u8 = u8::MAX;
+
+#[derive(PartialEq, Debug, Clone, Copy, Default)]
+pub struct ThistlethwaiteSolver {}
+
+// Orders of corners reached with half turns only, and for every order of
+// the corners the class of orders reached from it by renaming the corners
+// with one of those.
+struct CornerGroups {
+    half_turn_index: Vec<u8>,
+    classes: Vec<u16>,
+}
+
+impl ThistlethwaiteSolver {
+    pub fn new() -> ThistlethwaiteSolver {
+        ThistlethwaiteSolver {}
+    }
+
+    // Takes the cube through the nested groups G1, G2 and G3 to solved, with
+    // a stage per phase using fewer kinds of moves than the one before.
+    pub fn solve(&self, cube: &RubiksCube) -> Result<Solution, SolveError> {
+        let mut solving = SolvingCube::new(cube)?;
+        for (phase, description) in PHASE_DESCRIPTIONS.iter().enumerate() {
+            let depths = &phase_tables()[phase];
+            let index = |cube: &CubieCube| phase_index(phase, cube);
+            while depths[index(&solving.cube)] > 0 {
+                let depth = depths[index(&solving.cube)];
+                let cube_move = PHASE_MOVES[phase]
+                    .iter()
+                    .map(|m| Move::face_turn(*m))
+                    .find(|m| depths[index(&solving.cube.apply_move(*m))] < depth)
+                    .unwrap();
+                solving.apply_algorithm(&Algorithm::new(vec![cube_move]));
+            }
+            solving.finish_stage(&format!("phase {}", phase + 1), description.to_string());
+        }
+        Ok(solving.into_solution())
+    }
+
+    // The moves phase 1 to 4 are made of, None for any other phase.
+    pub fn phase_moves(phase: usize) -> Option<Vec<Move>> {
+        let moves = PHASE_MOVES.get(phase.checked_sub(1)?)?;
+        Some(moves.iter().map(|m| Move::face_turn(*m)).collect())
+    }
+
+    // Whether the cube can be solved with <U, D, L, R, F2, B2>: every edge
+    // is oriented.
+    pub fn in_g1(cube: &CubieCube) -> bool {
+        cube.flip() == 0
+    }
+
+    // Whether the cube can be solved with <U, D, L2, R2, F2, B2>: it is in
+    // G1, every corner is oriented and the middle layer edges are in it.
+    pub fn in_g2(cube: &CubieCube) -> bool {
+        ThistlethwaiteSolver::in_g1(cube) && cube.twist() == 0 && cube.slice_sorted() < 24
+    }
+
+    // Whether the cube can be solved with half turns only: it is in G2,
+    // every edge is in its slice, and the corners are in an order half
+    // turns reach.
+    pub fn in_g3(cube: &CubieCube) -> bool {
+        ThistlethwaiteSolver::in_g2(cube)
+            && slice_mask(cube) == 0
+            && corner_groups().half_turn_index[cube.corners()] != UNKNOWN
+    }
+}
+
+// Which up and down layer edge positions hold an edge of the other slice.
+fn slice_mask(cube: &CubieCube) -> usize {
+    M_EDGES
+        .iter()
+        .chain(S_EDGES.iter())
+        .enumerate()
+        .filter(|(_, position)| {
+            let edge = cube.edge_permutation[**position] as usize;
+            M_EDGES.contains(&edge) != M_EDGES.contains(position)
+        })
+        .fold(0, |mask, (i, _)| mask | 1 << i)
+}
+
+// Numbers the orders of the pieces at four positions.
+fn slice_order(cube: &CubieCube, positions: &[usize; 4]) -> usize {
+    let edges: Vec<usize> = positions
+        .iter()
+        .map(|p| cube.edge_permutation[*p] as usize)
+        .collect();
+    (0..4).fold(0, |index, i| {
+        let smaller = edges[i + 1..].iter().filter(|e| **e < edges[i]).count();
+        index * (4 - i) + smaller
+    })
+}
+
+// The coordinate each phase brings to 0. Phase 3 numbers the cosets of G3
+// in G2, phase 4 the elements of G3.
+fn phase_index(phase: usize, cube: &CubieCube) -> usize {
+    match phase {
+        0 => cube.flip(),
+        1 => cube.twist() * SLICES + cube.slice_sorted() / 24,
+        2 => corner_groups().classes[cube.corners()] as usize * 256 + slice_mask(cube),
+        _ => {
+            let corners = corner_groups().half_turn_index[cube.corners()] as usize;
+            let orders = [
+                slice_order(cube, &M_EDGES),
+                slice_order(cube, &S_EDGES),
+                slice_order(cube, &E_EDGES),
+            ];
+            orders
+                .iter()
+                .fold(corners, |index, order| index * 24 + order)
+        }
+    }
+}
+
+fn corner_groups() -> &'static CornerGroups {
+    static GROUPS: OnceLock<CornerGroups> = OnceLock::new();
+    GROUPS.get_or_init(|| {
+        let mut half_turn_index = vec![UNKNOWN; CORNER_PERMUTATIONS];
+        let mut orders = vec![CubieCube::new()];
+        half_turn_index[0] = 0;
+        let mut i = 0;
+        while i < orders.len() {
+            for m in PHASE_MOVES[3].iter() {
+                let next = orders[i].apply_move(Move::face_turn(*m));
+                if half_turn_index[next.corners()] == UNKNOWN {
+                    half_turn_index[next.corners()] = orders.len() as u8;
+                    orders.push(next);
+                }
+            }
+            i += 1;
+        }
+        let mut classes = vec![u16::MAX; CORNER_PERMUTATIONS];
+        let mut count = 0;
+        for corners in 0..CORNER_PERMUTATIONS {
+            if classes[corners] != u16::MAX {
+                continue;
+            }
+            let cube = CubieCube::new().with_corners(corners);
+            for renaming in orders.iter() {
+                classes[renaming.multiply(cube).corners()] = count;
+            }
+            count += 1;
+        }
+        CornerGroups {
+            half_turn_index,
+            classes,
+        }
+    })
+}
+
+// For each phase, how many of its moves each value of its coordinate is
+// from 0, found by a breadth first search from the solved cube.
+fn phase_tables() -> &'static Vec<Vec<u8>> {
+    static TABLES: OnceLock<Vec<Vec<u8>>> = OnceLock::new();
+    TABLES.get_or_init(|| {
+        let sizes = [FLIPS, TWISTS * SLICES, 420 * 256, 96 * 24 * 24 * 24];
+        sizes
+            .iter()
+            .enumerate()
+            .map(|(phase, size)| {
+                let mut depths = vec![UNKNOWN; *size];
+                depths[0] = 0;
+                let mut layer = vec![CubieCube::new()];
+                let mut depth = 0;
+                while !layer.is_empty() {
+                    depth += 1;
+                    let mut next_layer = Vec::new();
+                    for cube in layer {
+                        for m in PHASE_MOVES[phase].iter() {
+                            let next = cube.apply_move(Move::face_turn(*m));
+                            let index = phase_index(phase, &next);
+                            if depths[index] == UNKNOWN {
+                                depths[index] = depth;
+                                next_layer.push(next);
+                            }
+                        }
+                    }
+                    layer = next_layer;
+                }
+                depths
+            })
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cube_after(algorithm: &str) -> CubieCube {
+        CubieCube::new().apply(&algorithm.parse().unwrap())
+    }
+
+    #[test]
+    fn test_subgroup_predicates_should_tell_the_groups_apart() {
+        let solved = CubieCube::new();
+        assert!(ThistlethwaiteSolver::in_g1(&solved));
+        assert!(ThistlethwaiteSolver::in_g2(&solved));
+        assert!(ThistlethwaiteSolver::in_g3(&solved));
+        assert!(!ThistlethwaiteSolver::in_g1(&cube_after("F")));
+        let r = cube_after("R");
+        assert!(ThistlethwaiteSolver::in_g1(&r));
+        assert!(!ThistlethwaiteSolver::in_g2(&r));
+        let u = cube_after("U");
+        assert!(ThistlethwaiteSolver::in_g2(&u));
+        assert!(!ThistlethwaiteSolver::in_g3(&u));
+        assert!(ThistlethwaiteSolver::in_g3(&cube_after(
+            "R2 U2 F2 D2 L2 B2 U2"
+        )));
+    }
+
+    #[test]
+    fn test_phase_moves_should_stay_in_the_group_of_the_phase() {
+        let groups: [fn(&CubieCube) -> bool; 3] = [
+            ThistlethwaiteSolver::in_g1,
+            ThistlethwaiteSolver::in_g2,
+            ThistlethwaiteSolver::in_g3,
+        ];
+        assert_eq!(ThistlethwaiteSolver::phase_moves(1).unwrap().len(), 18);
+        assert_eq!(ThistlethwaiteSolver::phase_moves(0), None);
+        assert_eq!(ThistlethwaiteSolver::phase_moves(5), None);
+        for (phase, in_group) in groups.iter().enumerate() {
+            let moves = ThistlethwaiteSolver::phase_moves(phase + 2).unwrap();
+            assert!(moves
+                .iter()
+                .all(|m| in_group(&CubieCube::new().apply_move(*m))));
+        }
+    }
+
+    #[test]
+    fn test_solve_should_reach_each_group_in_turn() {
+        let solver = ThistlethwaiteSolver::new();
+        let scrambles = [
+            "R U R' U'",
+            "F2 D' L B2 R' U2 F' D2 L2 B R2 U' F L' D B' R U2 L F'",
+            "U R2 F B R B2 R U2 L B2 R U' D' R2 F R' L B2 U2 F2",
+            "M' E S2 r U2 x y' F' D",
+        ];
+        for scramble in scrambles.iter() {
+            let cube = RubiksCube::new().apply(&scramble.parse().unwrap());
+            let solution = solver.solve(&cube).unwrap();
+            assert!(cube.apply(&solution.algorithm()).is_solved());
+            let mut cubie_cube = CubieCube::from_rubiks_cube(&cube).unwrap();
+            let groups = [
+                ThistlethwaiteSolver::in_g1,
+                ThistlethwaiteSolver::in_g2,
+                ThistlethwaiteSolver::in_g3,
+            ];
+            for (phase, stage) in solution.stages.iter().enumerate() {
+                assert_eq!(stage.name, format!("phase {}", phase + 1));
+                let moves = ThistlethwaiteSolver::phase_moves(phase + 1).unwrap();
+                assert!(stage.moves.moves.iter().all(|m| moves.contains(m)));
+                cubie_cube = cubie_cube.apply(&stage.moves);
+                if phase < 3 {
+                    assert!(groups[phase](&cubie_cube));
+                }
+            }
+            assert!(solution.len() <= 52);
+        }
+    }
+}
@@ -0,0 +1,269 @@
+use crate::two_phase_tables::{MOVE_COUNT, PHASE2_MOVES};
+use crate::Algorithm;
+use crate::CubieCube;
+use crate::Move;
+use crate::RubiksCube;
+use crate::SolveError;
+use crate::TwoPhaseTables;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone)]
+pub struct TwoPhaseSolver {
+    pub max_length: usize,
+    pub timeout: Duration,
+    tables: Arc<TwoPhaseTables>,
+}
+
+impl Default for TwoPhaseSolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TwoPhaseSolver {
+    pub fn new() -> TwoPhaseSolver {
+        TwoPhaseSolver::with_tables(TwoPhaseTables::shared())
+    }
+
+    pub fn with_tables(tables: Arc<TwoPhaseTables>) -> TwoPhaseSolver {
+        TwoPhaseSolver {
+            max_length: 21,
+            timeout: Duration::from_secs(3),
+            tables,
+        }
+    }
+
+    pub fn from_cache<P: AsRef<Path>>(path: P) -> std::io::Result<TwoPhaseSolver> {
+        let tables = TwoPhaseTables::load_or_generate(path)?;
+        Ok(TwoPhaseSolver::with_tables(Arc::new(tables)))
+    }
+
+    pub fn max_length(self, max_length: usize) -> TwoPhaseSolver {
+        TwoPhaseSolver { max_length, ..self }
+    }
+
+    // A timeout too long to be reached, such as `Duration::MAX`, lets the
+    // search run until it is done.
+    pub fn timeout(self, timeout: Duration) -> TwoPhaseSolver {
+        TwoPhaseSolver { timeout, ..self }
+    }
+
+    // The solution turns the faces of the cube as it is held, so applying it
+    // leaves every face one color without changing the orientation.
+    pub fn solve(&self, cube: &RubiksCube) -> Result<Algorithm, SolveError> {
+        cube.validate()?;
+        self.solve_cubie_cube(&CubieCube::from_rubiks_cube(cube)?)
+    }
+
+    pub fn solve_cubie_cube(&self, cube: &CubieCube) -> Result<Algorithm, SolveError> {
+        cube.validate()?;
+        let mut search = Search {
+            tables: &self.tables,
+            cube: *cube,
+            moves: Vec::new(),
+            max_length: self.max_length,
+            deadline: Instant::now().checked_add(self.timeout),
+            nodes: 0,
+        };
+        let twist = cube.twist();
+        let flip = cube.flip();
+        let slice = cube.slice_sorted() / 24;
+        let start = self.tables.phase1_depth(twist, flip, slice) as usize;
+        for depth in start..=self.max_length {
+            match search.phase1(twist, flip, slice, depth) {
+                Some(true) => {
+                    let moves = search.moves.iter().map(|m| Move::face_turn(*m)).collect();
+                    return Ok(Algorithm::new(moves));
+                }
+                Some(false) => {}
+                None => break,
+            }
+        }
+        Err(SolveError::NotFound)
+    }
+}
+
+struct Search<'a> {
+    tables: &'a TwoPhaseTables,
+    cube: CubieCube,
+    moves: Vec<usize>,
+    max_length: usize,
+    deadline: Option<Instant>,
+    nodes: usize,
+}
+
+// The searches return None once the deadline has passed, and otherwise
+// whether a solution was found, leaving its moves in `moves`.
+impl Search<'_> {
+    fn phase1(&mut self, twist: usize, flip: usize, slice: usize, togo: usize) -> Option<bool> {
+        if togo == 0 {
+            return self.start_phase2();
+        }
+        self.check_deadline()?;
+        for m in 0..MOVE_COUNT {
+            if !self.follows(m) {
+                continue;
+            }
+            let tables = self.tables;
+            let twist = tables.twist_moves[twist * MOVE_COUNT + m] as usize;
+            let flip = tables.flip_moves[flip * MOVE_COUNT + m] as usize;
+            let slice = tables.slice_moves[slice * 24 * MOVE_COUNT + m] as usize / 24;
+            if tables.phase1_depth(twist, flip, slice) as usize >= togo {
+                continue;
+            }
+            self.moves.push(m);
+            if self.phase1(twist, flip, slice, togo - 1)? {
+                return Some(true);
+            }
+            self.moves.pop();
+        }
+        Some(false)
+    }
+
+    fn start_phase2(&mut self) -> Option<bool> {
+        // Ending phase 1 with a phase 2 move means a shorter phase 1 was
+        // already tried with the same continuations.
+        if let Some(last) = self.moves.last() {
+            if PHASE2_MOVES.contains(last) {
+                return Some(false);
+            }
+        }
+        let cube = self
+            .moves
+            .iter()
+            .fold(self.cube, |cube, m| cube.apply_move(Move::face_turn(*m)));
+        let corners = cube.corners();
+        let ud_edges = cube.ud_edges();
+        let slice = cube.slice_sorted();
+        let start = self.tables.phase2_depth(corners, ud_edges, slice) as usize;
+        let phase1_length = self.moves.len();
+        for depth in start..=self.max_length - phase1_length {
+            if self.phase2(corners, ud_edges, slice, depth)? {
+                return Some(true);
+            }
+        }
+        Some(false)
+    }
+
+    fn phase2(
+        &mut self,
+        corners: usize,
+        ud_edges: usize,
+        slice: usize,
+        togo: usize,
+    ) -> Option<bool> {
+        if togo == 0 {
+            return Some(corners == 0 && ud_edges == 0 && slice == 0);
+        }
+        self.check_deadline()?;
+        for m in PHASE2_MOVES.iter() {
+            if !self.follows(*m) {
+                continue;
+            }
+            let tables = self.tables;
+            let corners = tables.corner_moves[corners * MOVE_COUNT + m] as usize;
+            let ud_edges = tables.ud_edge_moves[ud_edges * MOVE_COUNT + m] as usize;
+            let slice = tables.slice_moves[slice * MOVE_COUNT + m] as usize;
+            if tables.phase2_depth(corners, ud_edges, slice) as usize >= togo {
+                continue;
+            }
+            self.moves.push(*m);
+            if self.phase2(corners, ud_edges, slice, togo - 1)? {
+                return Some(true);
+            }
+            self.moves.pop();
+        }
+        Some(false)
+    }
+
+    // Turning the same face twice in a row, or the down face before the up
+    // face, only gives sequences that are found in a shorter or other form.
+    fn follows(&self, m: usize) -> bool {
+        match self.moves.last() {
+            Some(last) => {
+                let face = m / 3;
+                let last_face = last / 3;
+                face != last_face && face + 3 != last_face
+            }
+            None => true,
+        }
+    }
+
+    fn check_deadline(&mut self) -> Option<()> {
+        self.nodes += 1;
+        match self.deadline {
+            Some(deadline) if self.nodes % 1024 == 0 && Instant::now() > deadline => None,
+            _ => Some(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ColorFacet;
+    use crate::ValidationError;
+
+    #[test]
+    fn test_solve_should_return_nothing_for_solved_cube() {
+        let solver = TwoPhaseSolver::new();
+        assert_eq!(solver.solve(&RubiksCube::new()), Ok(Algorithm::default()));
+    }
+
+    #[test]
+    fn test_solve_should_solve_scrambled_cubes() {
+        let solver = TwoPhaseSolver::new();
+        let scrambles = [
+            "R U R' U'",
+            "F2 D' L B2 R' U2 F' D2 L2 B R2 U' F L' D B' R U2 L F'",
+            "U R2 F B R B2 R U2 L B2 R U' D' R2 F R' L B2 U2 F2",
+            "M' E S2 r U2 x y' F' D",
+        ];
+        for scramble in scrambles.iter() {
+            let cube = RubiksCube::new().apply(&scramble.parse().unwrap());
+            let solution = solver.solve(&cube).unwrap();
+            assert!(solution.len() <= 21);
+            assert!(cube.apply(&solution).is_solved());
+        }
+    }
+
+    #[test]
+    fn test_solve_should_respect_max_length_and_timeout() {
+        let superflip: Algorithm = "U R2 F B R B2 R U2 L B2 R U' D' R2 F R' L B2 U2 F2"
+            .parse()
+            .unwrap();
+        let cube = RubiksCube::new().apply(&superflip);
+        let solver = TwoPhaseSolver::new()
+            .max_length(19)
+            .timeout(Duration::from_millis(50));
+        assert_eq!(solver.solve(&cube), Err(SolveError::NotFound));
+        // A solution this long is found well within the usual timeout, so
+        // only the deadline stops the search.
+        let solver = TwoPhaseSolver::new().max_length(22);
+        assert!(solver.solve(&cube).is_ok());
+        let solver = solver.timeout(Duration::from_nanos(1));
+        assert_eq!(solver.solve(&cube), Err(SolveError::NotFound));
+    }
+
+    #[test]
+    fn test_solve_should_reject_unsolvable_cube() {
+        let mut cube = RubiksCube::new();
+        cube.white.top.color = ColorFacet::Orange;
+        cube.orange.top.color = ColorFacet::White;
+        assert_eq!(
+            TwoPhaseSolver::new().solve(&cube),
+            Err(SolveError::Unsolvable(ValidationError::FlippedEdge))
+        );
+    }
+
+    #[test]
+    fn test_tables_should_load_what_was_saved() {
+        let path = std::env::temp_dir().join("rubiks-cube-two-phase-test.tables");
+        TwoPhaseTables::shared().save(&path).unwrap();
+        let solver = TwoPhaseSolver::from_cache(&path).unwrap();
+        assert_eq!(*solver.tables, *TwoPhaseTables::shared());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
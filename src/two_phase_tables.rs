@@ -0,0 +1,293 @@
+use crate::cubie_cube::FACES;
+use crate::ColorFacet;
+use crate::CubieCube;
+use crate::RotationDirection;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Error, ErrorKind, Read, Write};
+use std::path::Path;
+use std::sync::{Arc, OnceLock};
+
+pub(crate) const TWISTS: usize = 2187;
+pub(crate) const FLIPS: usize = 2048;
+pub(crate) const SLICES: usize = 495;
+pub(crate) const SLICE_SORTED: usize = 11880;
+pub(crate) const SLICE_PERMUTATIONS: usize = 24;
+pub(crate) const CORNERS: usize = 40320;
+pub(crate) const UD_EDGES: usize = 40320;
+
+// Moves are numbered as in `Move::face_turn`.
+pub(crate) const MOVE_COUNT: usize = 18;
+
+// U, D and half turns of the side faces, which keep the cube in the
+// subgroup reached by phase 1.
+pub(crate) const PHASE2_MOVES: [usize; 10] = [0, 1, 2, 4, 7, 9, 10, 11, 13, 16];
+
+const MAGIC: &[u8; 8] = b"RCTWOPH1";
+
+static SHARED: OnceLock<Arc<TwoPhaseTables>> = OnceLock::new();
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct TwoPhaseTables {
+    pub(crate) twist_moves: Vec<u16>,
+    pub(crate) flip_moves: Vec<u16>,
+    pub(crate) slice_moves: Vec<u16>,
+    pub(crate) corner_moves: Vec<u16>,
+    pub(crate) ud_edge_moves: Vec<u16>,
+    pub(crate) twist_slice_depths: Vec<u8>,
+    pub(crate) flip_slice_depths: Vec<u8>,
+    pub(crate) corner_slice_depths: Vec<u8>,
+    pub(crate) ud_edge_slice_depths: Vec<u8>,
+}
+
+impl Default for TwoPhaseTables {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TwoPhaseTables {
+    // Generates every table from scratch. The result is always the same, so
+    // it can be saved once and loaded afterwards.
+    pub fn new() -> TwoPhaseTables {
+        let all_moves: Vec<usize> = (0..MOVE_COUNT).collect();
+        let twist_moves = move_table(TWISTS, &all_moves, |coordinate, face, direction| {
+            CubieCube::new()
+                .with_twist(coordinate)
+                .rotate(face, direction)
+                .twist()
+        });
+        let flip_moves = move_table(FLIPS, &all_moves, |coordinate, face, direction| {
+            CubieCube::new()
+                .with_flip(coordinate)
+                .rotate(face, direction)
+                .flip()
+        });
+        let slice_moves = move_table(SLICE_SORTED, &all_moves, |coordinate, face, direction| {
+            CubieCube::new()
+                .with_slice_sorted(coordinate)
+                .rotate(face, direction)
+                .slice_sorted()
+        });
+        let corner_moves = move_table(CORNERS, &all_moves, |coordinate, face, direction| {
+            CubieCube::new()
+                .with_corners(coordinate)
+                .rotate(face, direction)
+                .corners()
+        });
+        let ud_edge_moves = move_table(UD_EDGES, &PHASE2_MOVES, |coordinate, face, direction| {
+            CubieCube::new()
+                .with_ud_edges(coordinate)
+                .rotate(face, direction)
+                .ud_edges()
+        });
+
+        let twist_slice_depths = depth_table(TWISTS * SLICES, &all_moves, |index, m| {
+            let twist = twist_moves[index / SLICES * MOVE_COUNT + m] as usize;
+            let slice = slice_moves[index % SLICES * 24 * MOVE_COUNT + m] as usize / 24;
+            twist * SLICES + slice
+        });
+        let flip_slice_depths = depth_table(FLIPS * SLICES, &all_moves, |index, m| {
+            let flip = flip_moves[index / SLICES * MOVE_COUNT + m] as usize;
+            let slice = slice_moves[index % SLICES * 24 * MOVE_COUNT + m] as usize / 24;
+            flip * SLICES + slice
+        });
+        let corner_slice_depths =
+            depth_table(CORNERS * SLICE_PERMUTATIONS, &PHASE2_MOVES, |index, m| {
+                let corners = corner_moves[index / SLICE_PERMUTATIONS * MOVE_COUNT + m] as usize;
+                let slice = slice_moves[index % SLICE_PERMUTATIONS * MOVE_COUNT + m] as usize;
+                corners * SLICE_PERMUTATIONS + slice
+            });
+        let ud_edge_slice_depths =
+            depth_table(UD_EDGES * SLICE_PERMUTATIONS, &PHASE2_MOVES, |index, m| {
+                let edges = ud_edge_moves[index / SLICE_PERMUTATIONS * MOVE_COUNT + m] as usize;
+                let slice = slice_moves[index % SLICE_PERMUTATIONS * MOVE_COUNT + m] as usize;
+                edges * SLICE_PERMUTATIONS + slice
+            });
+
+        TwoPhaseTables {
+            twist_moves,
+            flip_moves,
+            slice_moves,
+            corner_moves,
+            ud_edge_moves,
+            twist_slice_depths,
+            flip_slice_depths,
+            corner_slice_depths,
+            ud_edge_slice_depths,
+        }
+    }
+
+    // Tables shared by every solver of the process, generated the first time
+    // they are asked for.
+    pub fn shared() -> Arc<TwoPhaseTables> {
+        SHARED
+            .get_or_init(|| Arc::new(TwoPhaseTables::new()))
+            .clone()
+    }
+
+    // Loads the tables saved at `path`, or generates and saves them there if
+    // the file does not exist yet.
+    pub fn load_or_generate<P: AsRef<Path>>(path: P) -> std::io::Result<TwoPhaseTables> {
+        if path.as_ref().exists() {
+            return TwoPhaseTables::load(path);
+        }
+        let tables = TwoPhaseTables::new();
+        tables.save(path)?;
+        Ok(tables)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writer.write_all(MAGIC)?;
+        for table in self.move_tables() {
+            for value in table {
+                writer.write_all(&value.to_le_bytes())?;
+            }
+        }
+        for table in self.depth_tables() {
+            writer.write_all(table)?;
+        }
+        writer.flush()
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> std::io::Result<TwoPhaseTables> {
+        let mut reader = BufReader::new(File::open(path)?);
+        let mut magic = [0; 8];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "not a two-phase table file",
+            ));
+        }
+        let mut tables = TwoPhaseTables {
+            twist_moves: vec![0; TWISTS * MOVE_COUNT],
+            flip_moves: vec![0; FLIPS * MOVE_COUNT],
+            slice_moves: vec![0; SLICE_SORTED * MOVE_COUNT],
+            corner_moves: vec![0; CORNERS * MOVE_COUNT],
+            ud_edge_moves: vec![0; UD_EDGES * MOVE_COUNT],
+            twist_slice_depths: vec![0; TWISTS * SLICES],
+            flip_slice_depths: vec![0; FLIPS * SLICES],
+            corner_slice_depths: vec![0; CORNERS * SLICE_PERMUTATIONS],
+            ud_edge_slice_depths: vec![0; UD_EDGES * SLICE_PERMUTATIONS],
+        };
+        for table in tables.move_tables_mut() {
+            let mut bytes = vec![0; table.len() * 2];
+            reader.read_exact(&mut bytes)?;
+            for (value, pair) in table.iter_mut().zip(bytes.chunks(2)) {
+                *value = u16::from_le_bytes([pair[0], pair[1]]);
+            }
+        }
+        for table in tables.depth_tables_mut() {
+            reader.read_exact(table)?;
+        }
+        if reader.read(&mut [0])? != 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "trailing data after tables",
+            ));
+        }
+        Ok(tables)
+    }
+
+    pub(crate) fn phase1_depth(&self, twist: usize, flip: usize, slice: usize) -> u8 {
+        self.twist_slice_depths[twist * SLICES + slice]
+            .max(self.flip_slice_depths[flip * SLICES + slice])
+    }
+
+    pub(crate) fn phase2_depth(&self, corners: usize, ud_edges: usize, slice: usize) -> u8 {
+        self.corner_slice_depths[corners * SLICE_PERMUTATIONS + slice]
+            .max(self.ud_edge_slice_depths[ud_edges * SLICE_PERMUTATIONS + slice])
+    }
+
+    fn move_tables(&self) -> [&Vec<u16>; 5] {
+        [
+            &self.twist_moves,
+            &self.flip_moves,
+            &self.slice_moves,
+            &self.corner_moves,
+            &self.ud_edge_moves,
+        ]
+    }
+
+    fn move_tables_mut(&mut self) -> [&mut Vec<u16>; 5] {
+        [
+            &mut self.twist_moves,
+            &mut self.flip_moves,
+            &mut self.slice_moves,
+            &mut self.corner_moves,
+            &mut self.ud_edge_moves,
+        ]
+    }
+
+    fn depth_tables(&self) -> [&Vec<u8>; 4] {
+        [
+            &self.twist_slice_depths,
+            &self.flip_slice_depths,
+            &self.corner_slice_depths,
+            &self.ud_edge_slice_depths,
+        ]
+    }
+
+    fn depth_tables_mut(&mut self) -> [&mut Vec<u8>; 4] {
+        [
+            &mut self.twist_slice_depths,
+            &mut self.flip_slice_depths,
+            &mut self.corner_slice_depths,
+            &mut self.ud_edge_slice_depths,
+        ]
+    }
+}
+
+// Quarter turns are worked out on cubies and repeated through the table to
+// get the half and anticlockwise turns. Moves not listed are left at 0.
+pub(crate) fn move_table<F>(size: usize, moves: &[usize], turn: F) -> Vec<u16>
+where
+    F: Fn(usize, ColorFacet, RotationDirection) -> usize,
+{
+    let mut table = vec![0; size * MOVE_COUNT];
+    for (face_index, face) in FACES.iter().enumerate() {
+        let m = face_index * 3;
+        if moves.contains(&m) {
+            let clockwise: Vec<usize> = (0..size)
+                .map(|i| turn(i, *face, RotationDirection::Clockwise))
+                .collect();
+            for i in 0..size {
+                let mut coordinate = i;
+                for t in 0..3 {
+                    coordinate = clockwise[coordinate];
+                    table[i * MOVE_COUNT + m + t] = coordinate as u16;
+                }
+            }
+        } else if moves.contains(&(m + 1)) {
+            for i in 0..size {
+                table[i * MOVE_COUNT + m + 1] = turn(i, *face, RotationDirection::HalfTurn) as u16;
+            }
+        }
+    }
+    table
+}
+
+// Breadth first search from the solved state, 0, recording how many moves
+// each state is from it.
+pub(crate) fn depth_table<F>(size: usize, moves: &[usize], next: F) -> Vec<u8>
+where
+    F: Fn(usize, usize) -> usize,
+{
+    let mut depths = vec![u8::MAX; size];
+    let mut queue = VecDeque::new();
+    depths[0] = 0;
+    queue.push_back(0);
+    while let Some(index) = queue.pop_front() {
+        let depth = depths[index];
+        for m in moves {
+            let next_index = next(index, *m);
+            if depths[next_index] == u8::MAX {
+                depths[next_index] = depth + 1;
+                queue.push_back(next_index);
+            }
+        }
+    }
+    depths
+}
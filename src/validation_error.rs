@@ -0,0 +1,59 @@
+use crate::ColorFacet;
+use crate::Corner;
+use crate::Edge;
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum ValidationError {
+    InvalidCenters,
+    WrongColorCount(ColorFacet, usize),
+    UnknownCorner(Corner),
+    UnknownEdge(Edge),
+    DuplicateCorner(Corner),
+    DuplicateEdge(Edge),
+    MissingCorner(Corner),
+    MissingEdge(Edge),
+    TwistedCorner,
+    FlippedEdge,
+    PermutationParity,
+    TwistedCenter,
+}
+
+impl std::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ValidationError::InvalidCenters => write!(f, "centers do not have six distinct colors"),
+            ValidationError::WrongColorCount(color, count) => {
+                write!(f, "{:?} appears on {} facets instead of 9", color, count)
+            }
+            ValidationError::UnknownCorner(position) => {
+                write!(f, "stickers at corner {:?} match no corner", position)
+            }
+            ValidationError::UnknownEdge(position) => {
+                write!(f, "stickers at edge {:?} match no edge", position)
+            }
+            ValidationError::DuplicateCorner(corner) => {
+                write!(f, "corner {:?} appears more than once", corner)
+            }
+            ValidationError::DuplicateEdge(edge) => {
+                write!(f, "edge {:?} appears more than once", edge)
+            }
+            ValidationError::MissingCorner(corner) => write!(f, "corner {:?} is missing", corner),
+            ValidationError::MissingEdge(edge) => write!(f, "edge {:?} is missing", edge),
+            ValidationError::TwistedCorner => {
+                write!(f, "corner twists do not add up to a multiple of 3")
+            }
+            ValidationError::FlippedEdge => write!(f, "an odd number of edges is flipped"),
+            ValidationError::PermutationParity => {
+                write!(f, "corner and edge permutations have different parities")
+            }
+            ValidationError::TwistedCenter => {
+                write!(
+                    f,
+                    "center twists do not add up to an even number of quarter turns"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}